@@ -1,24 +1,215 @@
 use std::fmt::Debug;
 
-use crate::data_transfer_objects::Direction;
+use crate::data_transfer_objects::{self as dto, Direction};
 
 pub trait Controller: Debug {
     fn get_direction(&mut self) -> Direction;
+
+    /// Board-aware controllers override this, others ignore the board
+    fn get_direction_on(&mut self, _board: &dyn BoardView) -> Direction {
+        self.get_direction()
+    }
+}
+
+/// Read-only access to the board for board-aware controllers
+pub trait BoardView {
+    fn at(&self, position: &dto::Position) -> dto::Cell;
+
+    fn head(&self) -> dto::Position;
+
+    /// The position one step in `direction`, or `None` when that step leaves the board
+    fn move_in(&self, position: &dto::Position, direction: &Direction) -> Option<dto::Position>;
+
+    /// The direction the snake last moved in, unknown for a length 1 snake
+    fn heading(&self) -> Option<Direction> {
+        match self.at(&self.head()) {
+            dto::Cell::Snake(dto::Path {
+                entry: Some(entry), ..
+            }) => Some(entry.opposite()),
+            _ => None,
+        }
+    }
+
+    /// The snake from head to tail
+    fn snake(&self) -> Vec<dto::Position> {
+        let mut position = self.head();
+        let mut snake = vec![position];
+        while let dto::Cell::Snake(dto::Path {
+            entry: Some(entry), ..
+        }) = self.at(&position)
+        {
+            position = self.move_in(&position, &entry).expect("snake on board");
+            snake.push(position);
+        }
+        snake
+    }
+
+    /// Directions that neither reverse the snake nor run into it or an obstacle
+    fn safe_directions(&self) -> Vec<Direction> {
+        let head = self.head();
+        let reverse = self.heading().map(|heading| heading.opposite());
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| Some(*direction) != reverse)
+            .filter(|direction| {
+                self.move_in(&head, direction).is_some_and(|next| {
+                    !matches!(self.at(&next), dto::Cell::Snake(_) | dto::Cell::Obstacle)
+                })
+            })
+            .collect()
+    }
+}
+
+impl<const N_ROWS: usize, const N_COLS: usize> BoardView for [[dto::Cell; N_COLS]; N_ROWS] {
+    fn at(&self, (i, j): &dto::Position) -> dto::Cell {
+        self[*i][*j]
+    }
+
+    fn head(&self) -> dto::Position {
+        (0..N_ROWS)
+            .flat_map(|i| (0..N_COLS).map(move |j| (i, j)))
+            .find(|position| {
+                matches!(
+                    self.at(position),
+                    dto::Cell::Snake(dto::Path { exit: None, .. })
+                )
+            })
+            .expect("snake head")
+    }
+
+    fn move_in(&self, (i, j): &dto::Position, direction: &Direction) -> Option<dto::Position> {
+        let velocity = direction.as_velocity();
+        let i = (*i as isize + velocity.0).rem_euclid(N_ROWS as isize) as usize;
+        let j = (*j as isize + velocity.1).rem_euclid(N_COLS as isize) as usize;
+        Some((i, j))
+    }
+}
+
+#[cfg(test)]
+mod board_view_tests {
+    use super::*;
+
+    const PATH: dto::Path = dto::Path {
+        entry: Some(Direction::Left),
+        exit: None,
+    };
+
+    const BOARD: [[dto::Cell; 3]; 2] = [
+        [dto::Cell::Empty, dto::Cell::Foods, dto::Cell::Empty],
+        [
+            dto::Cell::Empty,
+            dto::Cell::Snake(dto::Path {
+                entry: None,
+                exit: Some(Direction::Right),
+            }),
+            dto::Cell::Snake(PATH),
+        ],
+    ];
+
+    #[test]
+    fn head() {
+        assert_eq!(BOARD.head(), (1, 2));
+    }
+
+    #[test]
+    fn move_in() {
+        assert_eq!(BOARD.move_in(&(1, 2), &Direction::Right), Some((1, 0)));
+        assert_eq!(BOARD.move_in(&(0, 0), &Direction::Up), Some((1, 0)));
+    }
+
+    #[test]
+    fn heading() {
+        assert_eq!(BOARD.heading(), Some(Direction::Right));
+    }
+
+    #[test]
+    fn snake() {
+        assert_eq!(BOARD.snake(), [(1, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn safe_directions() {
+        assert_eq!(
+            BOARD.safe_directions(),
+            [Direction::Right, Direction::Up, Direction::Down]
+        );
+    }
 }
 
 pub mod mock_controller {
+    use std::collections::VecDeque;
 
     use super::*;
 
     #[derive(Debug)]
     pub struct MockController(pub Direction);
 
+    impl MockController {
+        pub fn boxed(direction: Direction) -> Box<dyn Controller> {
+            Box::new(MockController(direction))
+        }
+    }
+
     impl Controller for MockController {
         fn get_direction(&mut self) -> Direction {
             self.0
         }
     }
 
+    /// Plays back directions in order, panicking once they run out
+    #[derive(Debug, Default)]
+    pub struct ScriptedController(pub VecDeque<Direction>);
+
+    impl ScriptedController {
+        pub fn boxed(directions: impl IntoIterator<Item = Direction>) -> Box<dyn Controller> {
+            Box::new(ScriptedController(directions.into_iter().collect()))
+        }
+    }
+
+    impl Controller for ScriptedController {
+        fn get_direction(&mut self) -> Direction {
+            self.0.pop_front().expect("more directions")
+        }
+    }
+
+    /// Plays back directions in order, counting polls past the end instead of panicking and
+    /// repeating the last direction for them
+    #[derive(Debug)]
+    pub struct StrictMockController {
+        directions: VecDeque<Direction>,
+        last: Direction,
+        overflow: usize,
+    }
+
+    impl StrictMockController {
+        pub fn new(directions: impl IntoIterator<Item = Direction>) -> StrictMockController {
+            StrictMockController {
+                directions: directions.into_iter().collect(),
+                last: Direction::Right,
+                overflow: 0,
+            }
+        }
+
+        pub fn boxed(directions: impl IntoIterator<Item = Direction>) -> Box<dyn Controller> {
+            Box::new(StrictMockController::new(directions))
+        }
+
+        /// How many polls came after the directions ran out
+        pub fn overflow(&self) -> usize {
+            self.overflow
+        }
+    }
+
+    impl Controller for StrictMockController {
+        fn get_direction(&mut self) -> Direction {
+            match self.directions.pop_front() {
+                Some(direction) => self.last = direction,
+                None => self.overflow += 1,
+            }
+            self.last
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -29,6 +220,43 @@ pub mod mock_controller {
             let mut controller = MockController(direction);
             assert_eq!(controller.get_direction(), direction);
         }
+
+        #[test]
+        fn scripted_get_direction() {
+            let directions = VecDeque::from([Direction::Up, Direction::Left]);
+            let mut controller = ScriptedController(directions);
+            assert_eq!(controller.get_direction(), Direction::Up);
+            assert_eq!(controller.get_direction(), Direction::Left);
+        }
+
+        #[test]
+        #[should_panic(expected = "more directions")]
+        fn scripted_get_direction_drained() {
+            ScriptedController::default().get_direction();
+        }
+
+        #[test]
+        fn scripted_boxed() {
+            let mut controller = ScriptedController::boxed([Direction::Up, Direction::Left]);
+            assert_eq!(controller.get_direction(), Direction::Up);
+            assert_eq!(controller.get_direction(), Direction::Left);
+        }
+
+        #[test]
+        fn strict_get_direction_overflow() {
+            let mut controller = StrictMockController::new([Direction::Up]);
+            assert_eq!(controller.get_direction(), Direction::Up);
+            assert_eq!(controller.overflow(), 0);
+            assert_eq!(controller.get_direction(), Direction::Up);
+            assert_eq!(controller.overflow(), 1);
+        }
+
+        #[test]
+        fn strict_boxed() {
+            let mut controller = StrictMockController::boxed([Direction::Up]);
+            assert_eq!(controller.get_direction(), Direction::Up);
+            assert_eq!(controller.get_direction(), Direction::Up);
+        }
     }
 }
 
@@ -37,7 +265,7 @@ pub mod random_controller {
     use rand::prelude::{Distribution, SeedableRng};
     use rand_chacha::ChaCha8Rng;
 
-    use crate::seeder::Seeder;
+    use crate::seeder::{MockSeeder, Seeder};
 
     use super::*;
 
@@ -53,6 +281,11 @@ pub mod random_controller {
             let direction = Distribution::<Direction>::sample(&Standard, &mut rng);
             RandomController { direction, rng }
         }
+
+        /// A controller seeded with `seed` ready to hand to a harness or `NonReversing`
+        pub fn boxed(seed: u64) -> Box<dyn Controller> {
+            Box::new(RandomController::new(&mut MockSeeder(seed)))
+        }
     }
 
     impl Controller for RandomController {
@@ -84,5 +317,416 @@ pub mod random_controller {
             let mut controller = RandomController::new(&mut seeder);
             assert_eq!(controller.get_direction(), Direction::Left);
         }
+
+        #[test]
+        fn boxed() {
+            let mut controller = RandomController::boxed(0);
+            assert_eq!(controller.get_direction(), Direction::Left);
+        }
+    }
+}
+
+pub mod safe_random_controller {
+    use rand::distributions::Standard;
+    use rand::prelude::{Distribution, SeedableRng};
+    use rand::Rng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::seeder::{MockSeeder, Seeder};
+
+    use super::*;
+
+    /// Samples uniformly among safe directions, only moving unsafely when it has to
+    #[derive(Debug)]
+    pub struct SafeRandomController {
+        rng: ChaCha8Rng,
+    }
+
+    impl SafeRandomController {
+        pub fn new(seeder: &mut dyn Seeder) -> SafeRandomController {
+            let rng = ChaCha8Rng::seed_from_u64(seeder.get_seed());
+            SafeRandomController { rng }
+        }
+
+        pub fn boxed(seed: u64) -> Box<dyn Controller> {
+            Box::new(SafeRandomController::new(&mut MockSeeder(seed)))
+        }
+    }
+
+    impl Controller for SafeRandomController {
+        fn get_direction(&mut self) -> Direction {
+            Distribution::sample(&Standard, &mut self.rng)
+        }
+
+        fn get_direction_on(&mut self, board: &dyn BoardView) -> Direction {
+            let directions = board.safe_directions();
+            if directions.is_empty() {
+                self.get_direction()
+            } else {
+                directions[self.rng.gen_range(0..directions.len())]
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::seeder::MockSeeder;
+
+        const fn snake(entry: Option<Direction>, exit: Option<Direction>) -> dto::Cell {
+            dto::Cell::Snake(dto::Path { entry, exit })
+        }
+
+        const BOARD: [[dto::Cell; 3]; 3] = [
+            [dto::Cell::Empty, dto::Cell::Empty, dto::Cell::Empty],
+            [
+                snake(Some(Direction::Down), Some(Direction::Right)),
+                snake(Some(Direction::Left), None),
+                snake(None, Some(Direction::Down)),
+            ],
+            [
+                snake(Some(Direction::Right), Some(Direction::Up)),
+                snake(Some(Direction::Right), Some(Direction::Left)),
+                snake(Some(Direction::Up), Some(Direction::Left)),
+            ],
+        ];
+
+        #[test]
+        fn get_direction_on_one_safe() {
+            for seed in 0..8 {
+                let mut controller = SafeRandomController::new(&mut MockSeeder(seed));
+                assert_eq!(controller.get_direction_on(&BOARD), Direction::Up);
+            }
+        }
+    }
+}
+
+pub mod tail_chase_controller {
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    use super::*;
+
+    /// Heads for the nearest food only when it can still reach its tail after eating,
+    /// otherwise follows its tail to stall safely
+    #[derive(Debug, Default)]
+    pub struct TailChaseController;
+
+    impl TailChaseController {
+        pub fn boxed() -> Box<dyn Controller> {
+            Box::new(TailChaseController)
+        }
+    }
+
+    impl Controller for TailChaseController {
+        fn get_direction(&mut self) -> Direction {
+            Direction::Right
+        }
+
+        fn get_direction_on(&mut self, board: &dyn BoardView) -> Direction {
+            let snake = board.snake();
+            food_direction(board, &snake)
+                .or_else(|| tail_direction(board, &snake))
+                .or_else(|| board.safe_directions().first().copied())
+                .or_else(|| board.heading())
+                .unwrap_or_else(|| self.get_direction())
+        }
+    }
+
+    fn food_direction(board: &dyn BoardView, snake: &[dto::Position]) -> Option<Direction> {
+        let body = HashSet::from_iter(snake.iter().copied());
+        let path = find_path(board, snake[0], &body, |position| {
+            board.at(position) == dto::Cell::Foods
+        })?;
+        let mut virtual_snake = VecDeque::from(snake.to_vec());
+        for (i, (_, position)) in path.iter().enumerate() {
+            virtual_snake.push_front(*position);
+            if i + 1 < path.len() {
+                virtual_snake.pop_back();
+            }
+        }
+        let tail = *virtual_snake.back().expect("snake tail");
+        let body = HashSet::from_iter(virtual_snake.iter().copied());
+        find_path(board, virtual_snake[0], &body, |position| *position == tail)?;
+        path.first().map(|(direction, _)| *direction)
+    }
+
+    fn tail_direction(board: &dyn BoardView, snake: &[dto::Position]) -> Option<Direction> {
+        let tail = *snake.last().expect("snake tail");
+        let body = HashSet::from_iter(snake.iter().copied());
+        let path = find_path(board, snake[0], &body, |position| *position == tail)?;
+        if path.len() > 1 {
+            path.first().map(|(direction, _)| *direction)
+        } else {
+            None
+        }
+    }
+
+    /// The steps of a shortest path to a goal through unblocked cells, excluding `start`
+    fn find_path(
+        board: &dyn BoardView,
+        start: dto::Position,
+        blocked: &HashSet<dto::Position>,
+        is_goal: impl Fn(&dto::Position) -> bool,
+    ) -> Option<Vec<(Direction, dto::Position)>> {
+        let mut parents = HashMap::new();
+        let mut is_visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(position) = queue.pop_front() {
+            for direction in Direction::ALL {
+                let Some(next) = board.move_in(&position, &direction) else {
+                    continue;
+                };
+                if is_visited.contains(&next) {
+                    continue;
+                }
+                if is_goal(&next) {
+                    let mut path = vec![(direction, next)];
+                    let mut position = position;
+                    while position != start {
+                        let (direction, parent) = parents[&position];
+                        path.push((direction, position));
+                        position = parent;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if !blocked.contains(&next) && board.at(&next) != dto::Cell::Obstacle {
+                    is_visited.insert(next);
+                    parents.insert(next, (direction, position));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::game_state::Options;
+        use crate::view::MockView;
+
+        #[test]
+        fn find_path() {
+            let board = [[dto::Cell::Empty; 3]; 3];
+            let blocked = HashSet::from([(0, 1)]);
+            let path = super::find_path(&board, (0, 0), &blocked, |position| *position == (0, 2));
+            assert_eq!(path, Some(vec![(Direction::Left, (0, 2))]));
+        }
+
+        #[test]
+        fn get_direction_on_eats() {
+            let mut controller = TailChaseController;
+            let mut view = MockView::default();
+            let mut game_state = Options::<5, 5>::with_seed(2, 0)
+                .build(&mut controller, &mut view)
+                .unwrap();
+            for _ in 0..40 {
+                assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+            }
+            assert!(game_state.score() >= 8);
+        }
+    }
+}
+
+pub mod non_reversing_controller {
+    use super::*;
+
+    /// Wraps any controller, going straight instead whenever it asks to reverse
+    #[derive(Debug)]
+    pub struct NonReversing {
+        inner: Box<dyn Controller>,
+        last: Option<Direction>,
+    }
+
+    impl NonReversing {
+        pub fn new(inner: Box<dyn Controller>) -> NonReversing {
+            NonReversing { inner, last: None }
+        }
+
+        pub fn boxed(inner: Box<dyn Controller>) -> Box<dyn Controller> {
+            Box::new(NonReversing::new(inner))
+        }
+
+        fn forward(&mut self, direction: Direction) -> Direction {
+            let direction = match self.last {
+                Some(last) if direction == last.opposite() => last,
+                _ => direction,
+            };
+            self.last = Some(direction);
+            direction
+        }
+    }
+
+    impl Controller for NonReversing {
+        fn get_direction(&mut self) -> Direction {
+            let direction = self.inner.get_direction();
+            self.forward(direction)
+        }
+
+        fn get_direction_on(&mut self, board: &dyn BoardView) -> Direction {
+            let direction = self.inner.get_direction_on(board);
+            self.forward(direction)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::collections::VecDeque;
+
+        use super::*;
+        use crate::controller::mock_controller::ScriptedController;
+
+        #[test]
+        fn get_direction_reversal() {
+            let directions = VecDeque::from([
+                Direction::Right,
+                Direction::Left,
+                Direction::Left,
+                Direction::Up,
+            ]);
+            let inner = Box::new(ScriptedController(directions));
+            let mut controller = NonReversing::new(inner);
+            assert_eq!(controller.get_direction(), Direction::Right);
+            assert_eq!(controller.get_direction(), Direction::Right);
+            assert_eq!(controller.get_direction(), Direction::Right);
+            assert_eq!(controller.get_direction(), Direction::Up);
+        }
+    }
+}
+
+pub mod fn_controller {
+    use std::fmt;
+
+    use super::*;
+
+    /// Asks a closure for each direction, moving right when there's no board to show it
+    pub struct FnController<F: FnMut(&dyn BoardView) -> Direction>(pub F);
+
+    impl<F: FnMut(&dyn BoardView) -> Direction + 'static> FnController<F> {
+        pub fn boxed(f: F) -> Box<dyn Controller> {
+            Box::new(FnController(f))
+        }
+    }
+
+    impl<F: FnMut(&dyn BoardView) -> Direction> fmt::Debug for FnController<F> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("FnController").finish_non_exhaustive()
+        }
+    }
+
+    impl<F: FnMut(&dyn BoardView) -> Direction> Controller for FnController<F> {
+        fn get_direction(&mut self) -> Direction {
+            Direction::Right
+        }
+
+        fn get_direction_on(&mut self, board: &dyn BoardView) -> Direction {
+            (self.0)(board)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::game_state::Options;
+        use crate::view::MockView;
+
+        #[test]
+        fn get_direction_on() {
+            let mut controller = FnController(|_: &dyn BoardView| Direction::Down);
+            let mut view = MockView::default();
+            let mut game_state = Options::<3, 3>::with_seed(0, 0)
+                .build(&mut controller, &mut view)
+                .unwrap();
+            game_state.iterate_turn();
+            assert_eq!(game_state.snake_positions_vec(), [(2, 1)]);
+        }
+
+        #[test]
+        fn boxed() {
+            let mut controller = FnController::boxed(|_: &dyn BoardView| Direction::Down);
+            let mut view = MockView::default();
+            let mut game_state = Options::<3, 3>::with_seed(0, 0)
+                .build(&mut *controller, &mut view)
+                .unwrap();
+            game_state.iterate_turn();
+            assert_eq!(game_state.snake_positions_vec(), [(2, 1)]);
+        }
+    }
+}
+
+pub mod target_controller {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    /// Steers greedily toward a target others can move while the game holds the controller,
+    /// taking the safe direction that lands closest to it
+    #[derive(Debug)]
+    pub struct TargetController {
+        target: Rc<Cell<dto::Position>>,
+    }
+
+    impl TargetController {
+        pub fn new(target: dto::Position) -> TargetController {
+            TargetController {
+                target: Rc::new(Cell::new(target)),
+            }
+        }
+
+        /// A shared handle to the target for updating it from outside the game
+        pub fn target(&self) -> Rc<Cell<dto::Position>> {
+            Rc::clone(&self.target)
+        }
+
+        pub fn set_target(&self, target: dto::Position) {
+            self.target.set(target);
+        }
+    }
+
+    impl Controller for TargetController {
+        fn get_direction(&mut self) -> Direction {
+            Direction::Right
+        }
+
+        fn get_direction_on(&mut self, board: &dyn BoardView) -> Direction {
+            let head = board.head();
+            let (i, j) = self.target.get();
+            board
+                .safe_directions()
+                .into_iter()
+                .filter_map(|direction| Some((direction, board.move_in(&head, &direction)?)))
+                .min_by_key(|(_, (next_i, next_j))| next_i.abs_diff(i) + next_j.abs_diff(j))
+                .map(|(direction, _)| direction)
+                .or_else(|| board.heading())
+                .unwrap_or_else(|| self.get_direction())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const HEAD: dto::Cell = dto::Cell::Snake(dto::Path {
+            entry: None,
+            exit: None,
+        });
+
+        const BOARD: [[dto::Cell; 3]; 3] = [
+            [dto::Cell::Empty; 3],
+            [dto::Cell::Empty, HEAD, dto::Cell::Empty],
+            [dto::Cell::Empty; 3],
+        ];
+
+        #[test]
+        fn get_direction_on() {
+            let mut controller = TargetController::new((1, 2));
+            assert_eq!(controller.get_direction_on(&BOARD), Direction::Right);
+            controller.target().set((0, 1));
+            assert_eq!(controller.get_direction_on(&BOARD), Direction::Up);
+            controller.set_target((2, 0));
+            assert_eq!(controller.get_direction_on(&BOARD), Direction::Left);
+        }
     }
 }
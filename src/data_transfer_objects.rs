@@ -1,6 +1,12 @@
+use std::str::FromStr;
+
 pub type Position = (usize, usize);
 
+/// The board's cells row by row
+pub type Frame = Vec<Vec<Cell>>;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Right,
     Up,
@@ -9,6 +15,24 @@ pub enum Direction {
 }
 
 #[derive(Debug, PartialEq)]
+pub struct InvalidDirection;
+
+/// Parses a direction's name in any case, or its `w`, `a`, `s`, or `d` key
+impl FromStr for Direction {
+    type Err = InvalidDirection;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "right" | "d" => Ok(Direction::Right),
+            "up" | "w" => Ok(Direction::Up),
+            "left" | "a" => Ok(Direction::Left),
+            "down" | "s" => Ok(Direction::Down),
+            _ => Err(InvalidDirection),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Status {
     Ongoing,
     Over { is_won: bool },
@@ -19,6 +43,7 @@ pub enum Cell {
     Empty,
     Foods,
     Snake(Path),
+    Obstacle,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -26,3 +51,50 @@ pub struct Path {
     pub entry: Option<Direction>,
     pub exit: Option<Direction>,
 }
+
+/// Parses a fixture row where `.` is empty, `*` is food, `%` is an obstacle, and `#` or a
+/// letter is a snake cell without a path, panicking on anything else
+pub fn cells_from_str(row: &str) -> Vec<Cell> {
+    row.chars()
+        .map(|c| match c {
+            '.' => Cell::Empty,
+            '*' => Cell::Foods,
+            '%' => Cell::Obstacle,
+            '#' | 'a'..='z' | 'A'..='Z' => Cell::Snake(Path {
+                entry: None,
+                exit: None,
+            }),
+            _ => panic!("unexpected fixture char {c:?}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction_from_str() {
+        assert_eq!("Up".parse(), Ok(Direction::Up));
+        assert_eq!("a".parse(), Ok(Direction::Left));
+        assert_eq!("D".parse(), Ok(Direction::Right));
+        assert_eq!("north".parse::<Direction>(), Err(InvalidDirection));
+    }
+
+    #[test]
+    fn cells_from_str() {
+        let snake = Cell::Snake(Path {
+            entry: None,
+            exit: None,
+        });
+        assert_eq!(
+            super::cells_from_str(".*#"),
+            [Cell::Empty, Cell::Foods, snake]
+        );
+        assert_eq!(super::cells_from_str("h"), [snake]);
+        assert_eq!(
+            super::cells_from_str("%.X"),
+            [Cell::Obstacle, Cell::Empty, snake]
+        );
+    }
+}
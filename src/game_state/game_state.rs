@@ -1,28 +1,78 @@
-use crate::controller::Controller;
+use std::any::Any;
+use std::collections::{HashSet, VecDeque};
+use std::f64::consts::TAU;
+use std::{fmt, mem};
+
+use crate::controller::{BoardView, Controller};
 use crate::data_transfer_objects as dto;
-use crate::view::View;
-use rand::Rng;
+use crate::seeder::MockSeeder;
+use crate::view::{CellTheme, View};
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
 use super::{
-    options::Options,
-    state::{board::Board, state::State, *},
+    options::{InvalidOptions, Options, Settings, TieBreak},
+    state::{
+        board::{Board, NoSnakeHead},
+        state::{InvalidState, State},
+        *,
+    },
 };
 
 // TODO: replace `view` with subscription model
 // TODO: some testing for `iterate_turn` is redundant
 
 #[derive(Debug)]
-pub struct MaxFoods;
+enum NoFood {
+    MaxFoods,
+    FoodSequence(FoodSequenceError),
+}
+
+/// Why a food sequence couldn't place the next food
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FoodSequenceError {
+    /// Every position in the sequence has been used
+    Exhausted,
+    /// The next position in the sequence isn't empty
+    Occupied(dto::Position),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NoRoom;
+
+/// Plain glyphs matching `dto::cells_from_str` for readable test output
+const ASCII_THEME: CellTheme = CellTheme {
+    empty: '.',
+    food: '*',
+    snake: '#',
+    obstacle: '%',
+};
 
 #[derive(Debug)]
 pub struct GameState<'a, const N_ROWS: usize, const N_COLS: usize> {
-    state: State<N_ROWS, N_COLS>,
-    controller: &'a mut dyn Controller,
-    view: &'a mut dyn View,
+    pub(super) state: State<N_ROWS, N_COLS>,
+    pub(super) controller: &'a mut dyn Controller,
+    pub(super) view: &'a mut dyn View,
+    pub(super) heading: Option<Direction>,
+    pub(super) score: usize,
+    /// The settings the game was built with, which runtime changes like `set_n_foods` leave
+    /// alone
+    pub(super) settings: Settings,
+    pub(super) n_foods: usize,
+    pub(super) seed: Option<u64>,
+    pub(super) directions: Vec<Direction>,
+    pub(super) status: dto::Status,
+    pub(super) pending_growth: usize,
+    pub(super) n_sequenced_foods: usize,
+    food_sequence_error: Option<FoodSequenceError>,
+    pub(super) spawn_countdown: usize,
+    pub(super) changes: Vec<(dto::Position, dto::Cell, dto::Cell)>,
 }
 
 impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS> {
+    /// How many directions `recent_directions` keeps
+    pub const RECENT_DIRECTIONS: usize = 4;
+
     pub fn from_options(
         options: &Options<N_ROWS, N_COLS>,
         controller: &'a mut dyn Controller,
@@ -31,6 +81,7 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
         let board = Board::<N_ROWS, N_COLS>::default();
         let mut game_state = options.get_init_game_state(board, controller, view);
         options.add_foods(&mut game_state);
+        game_state.send_board();
         game_state
     }
 
@@ -41,20 +92,137 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
         view: &'a mut dyn View,
         rng: ChaCha8Rng,
     ) -> GameState<'a, N_ROWS, N_COLS> {
+        GameState::new(State::new(board, rng), controller, view)
+    }
+
+    /// Like `from_board` but fails instead of panicking when the board has no snake head
+    pub fn try_from_board(
+        board: Board<N_ROWS, N_COLS>,
+        controller: &'a mut dyn Controller,
+        view: &'a mut dyn View,
+        rng: ChaCha8Rng,
+    ) -> Result<GameState<'a, N_ROWS, N_COLS>, NoSnakeHead> {
+        let state = State::try_new(board, rng)?;
+        Ok(GameState::new(state, controller, view))
+    }
+
+    /// This builds a `GameState` from an explicit `State` for test setup
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn from_parts(
+        state: State<N_ROWS, N_COLS>,
+        controller: &'a mut dyn Controller,
+        view: &'a mut dyn View,
+    ) -> GameState<'a, N_ROWS, N_COLS> {
+        GameState::new(state, controller, view)
+    }
+
+    fn new(
+        state: State<N_ROWS, N_COLS>,
+        controller: &'a mut dyn Controller,
+        view: &'a mut dyn View,
+    ) -> GameState<'a, N_ROWS, N_COLS> {
+        let heading = state.heading();
+        let settings = Settings {
+            n_foods: state.foods.len(),
+            obstacles: state
+                .board
+                .iter()
+                .filter(|(_, cell)| *cell == Cell::Obstacle)
+                .map(|(position, _)| position.into())
+                .collect(),
+            topology: state.topology,
+            ..Settings::default()
+        };
+        let n_foods = settings.n_foods;
+        let status = state.check_is_won_status();
         GameState {
-            state: State::new(board, rng),
+            state,
             controller,
             view,
+            heading,
+            score: 0,
+            settings,
+            n_foods,
+            seed: None,
+            directions: Vec::new(),
+            status,
+            pending_growth: 0,
+            n_sequenced_foods: 0,
+            food_sequence_error: None,
+            spawn_countdown: 0,
+            changes: Vec::new(),
         }
     }
 
+    /// Plays a turn in the controller's direction, or returns the status without asking the
+    /// controller once the game is over
     pub fn iterate_turn(&mut self) -> dto::Status {
-        let direction = self.controller.get_direction();
-        let next_head = self.state.get_next_head(&direction);
-        match self.state.board.at(&next_head) {
+        if self.status != dto::Status::Ongoing {
+            return self.status;
+        }
+        let direction = self.controller.get_direction_on(&self.state);
+        self.iterate_turn_with(direction)
+    }
+
+    /// Plays a turn like `iterate_turn`, failing when the food sequence couldn't place a food
+    pub fn try_iterate_turn(&mut self) -> Result<dto::Status, FoodSequenceError> {
+        if self.status != dto::Status::Ongoing {
+            return Ok(self.status);
+        }
+        let direction = self.controller.get_direction_on(&self.state);
+        self.try_iterate_turn_with(direction)
+    }
+
+    /// Plays a turn like `iterate_turn_with`, failing when the food sequence couldn't place a
+    /// food, in which case the turn is still played without it
+    pub fn try_iterate_turn_with(
+        &mut self,
+        direction: Direction,
+    ) -> Result<dto::Status, FoodSequenceError> {
+        self.food_sequence_error = None;
+        let status = self.iterate_turn_with(direction);
+        match self.food_sequence_error.take() {
+            Some(error) => Err(error),
+            None => Ok(status),
+        }
+    }
+
+    /// Plays a turn in `direction` without consulting the controller, doing nothing once the
+    /// game is over
+    pub fn iterate_turn_with(&mut self, direction: Direction) -> dto::Status {
+        if self.status != dto::Status::Ongoing {
+            return self.status;
+        }
+        // A length 1 snake can't reverse its seeded heading, so it keeps going straight
+        let direction = match self.heading {
+            Some(heading)
+                if self.snake_len() == 1 && Some(direction) == self.forbidden_direction() =>
+            {
+                heading
+            }
+            _ => direction,
+        };
+        self.changes.clear();
+        self.tick_food_spawn();
+        self.directions.push(direction);
+        let last_head = *self.get_last_head();
+        let Some(next_head) = self.state.get_next_head(&direction) else {
+            return self.set_status(dto::Status::Over { is_won: false });
+        };
+        let status = match self.state.board.at(&next_head) {
+            Cell::Empty(_) if self.pending_growth > 0 => {
+                self.seam_crossed(last_head, next_head, &direction);
+                self.pending_growth -= 1;
+                self.update_last_head(&direction);
+                self.insert_snake_head(next_head, Some(direction.opposite()));
+                self.heading = Some(direction);
+                self.state.check_is_won_status()
+            }
             Cell::Empty(_) => {
+                self.seam_crossed(last_head, next_head, &direction);
+                let old = self.state.board.at(self.get_next_tail()).into();
                 let last_tail = self.state.remove_last_tail();
-                self.cell_updated(last_tail);
+                self.swap_cell(last_tail, old);
                 let entry = if self.state.snake.is_empty() {
                     None
                 } else {
@@ -63,15 +231,594 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
                     Some(direction.opposite())
                 };
                 self.insert_snake_head(next_head, entry);
+                self.heading = Some(direction);
                 dto::Status::Ongoing
             }
             Cell::Foods(_) => {
+                self.seam_crossed(last_head, next_head, &direction);
                 self.update_last_head(&direction);
                 self.insert_snake_head(next_head, Some(direction.opposite()));
-                let _ = self.insert_food();
+                if self.settings.food_spawn_delay == 0 && self.state.foods.len() < self.n_foods {
+                    self.spawn_food();
+                }
+                self.heading = Some(direction);
+                self.pending_growth += self.settings.growth_per_food - 1;
+                self.score += 1;
                 self.state.check_is_won_status()
             }
-            Cell::Snake { .. } => dto::Status::Over { is_won: false },
+            Cell::Snake { .. } | Cell::Obstacle => dto::Status::Over { is_won: false },
+        };
+        debug_assert!(self.state.is_valid(), "{:?}", self.state.validate());
+        debug_assert!(!self.snake_self_intersects(), "{:?}", self.state.snake);
+        self.set_status(status)
+    }
+
+    /// Keeps the first `Over` status so nothing brings an ended game back
+    fn set_status(&mut self, status: dto::Status) -> dto::Status {
+        if self.status == dto::Status::Ongoing {
+            if let dto::Status::Over { is_won } = status {
+                self.view.on_game_over(is_won);
+            }
+            self.status = status;
+        }
+        self.status
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    /// The view as `V`, or `None` when it's another type, so a caller can flush or present it
+    /// between turns while the game borrows it
+    pub fn view_mut<V: View>(&mut self) -> Option<&mut V> {
+        let view: &mut dyn Any = &mut *self.view;
+        view.downcast_mut()
+    }
+
+    /// Segments still queued to grow from foods already eaten
+    pub fn pending_growth(&self) -> usize {
+        self.pending_growth
+    }
+
+    /// Whether the snake fills every cell that isn't an obstacle
+    pub fn is_board_full(&self) -> bool {
+        self.state.empty.is_empty() && self.state.foods.is_empty()
+    }
+
+    /// The direction the snake last moved in, or started facing with a seeded heading
+    pub fn heading(&self) -> Option<Direction> {
+        self.heading
+    }
+
+    /// Whether the snake wraps across any edge, a cylinder wrapping only one pair of them
+    pub fn wraps(&self) -> bool {
+        let topology = self.state.topology;
+        topology.wraps_horizontally() || topology.wraps_vertically()
+    }
+
+    /// Turns left until the next delayed food spawns, or `None` when the foods are at the cap
+    pub fn ticks_until_next_food(&self) -> Option<usize> {
+        (self.settings.food_spawn_delay > 0 && self.state.foods.len() < self.n_foods)
+            .then_some(self.spawn_countdown)
+    }
+
+    /// Checksum of the board for integrity checks, hashing each cell's kind and path
+    #[cfg(feature = "crc32")]
+    pub fn board_crc32(&self) -> u32 {
+        let direction_byte = |direction: Option<Direction>| {
+            direction.map_or(0, |direction| 1 + direction.clockwise_index())
+        };
+        let mut hasher = crc32fast::Hasher::new();
+        for (_, cell) in self.state.board.iter() {
+            let bytes = match dto::Cell::from(cell) {
+                dto::Cell::Empty => [0, 0, 0],
+                dto::Cell::Foods => [1, 0, 0],
+                dto::Cell::Snake(path) => {
+                    [2, direction_byte(path.entry), direction_byte(path.exit)]
+                }
+                dto::Cell::Obstacle => [3, 0, 0],
+            };
+            hasher.update(&bytes);
+        }
+        hasher.finalize()
+    }
+
+    /// How many cells the latest turn changed, `0` meaning there's nothing to redraw
+    pub fn cells_changed_last_turn(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// The board as cell kinds, `0` empty, `1` food, `2` snake, and `3` obstacle
+    #[cfg(feature = "ndarray")]
+    pub fn to_array2(&self) -> ndarray::Array2<u8> {
+        ndarray::Array2::from_shape_fn((N_ROWS, N_COLS), |(i, j)| {
+            match self.state.board.at(&Position(i, j)) {
+                Cell::Empty(_) => 0,
+                Cell::Foods(_) => 1,
+                Cell::Snake(_) => 2,
+                Cell::Obstacle => 3,
+            }
+        })
+    }
+
+    /// Every `(position, old, new)` cell change since the latest turn started, one per position,
+    /// with none before the first turn
+    pub fn last_changes(&self) -> &[(dto::Position, dto::Cell, dto::Cell)] {
+        &self.changes
+    }
+
+    /// The one direction that reverses into the snake, unknown for a length 1 snake unless it
+    /// hasn't moved from a seeded heading
+    pub fn forbidden_direction(&self) -> Option<Direction> {
+        if self.snake_len() > 1 || (self.settings.seeded_heading && self.directions.is_empty()) {
+            self.heading.map(|heading| heading.opposite())
+        } else {
+            None
+        }
+    }
+
+    /// Plays a turn toward the `w`, `a`, `s`, or `d` key, going straight instead of reversing,
+    /// and returns the status after the turn
+    pub fn apply_key(&mut self, key: char) -> Result<dto::Status, dto::InvalidDirection> {
+        let direction: Direction = key.to_string().parse()?;
+        let direction = if Some(direction) == self.forbidden_direction() {
+            direction.opposite()
+        } else {
+            direction
+        };
+        Ok(self.iterate_turn_with(direction))
+    }
+
+    /// The head cell's path, its entry pointing back along the body
+    pub fn head_path(&self) -> dto::Path {
+        let head = self.state.snake.front().expect("snake head");
+        match self.state.board.at(head) {
+            Cell::Snake(path) => path,
+            cell => panic!("invariant invalid head {cell:?}"),
+        }
+    }
+
+    /// The status after the latest turn
+    pub fn status(&self) -> dto::Status {
+        self.status
+    }
+
+    /// The board's rows top to bottom, one at a time
+    pub fn board_rows(&self) -> impl Iterator<Item = [dto::Cell; N_COLS]> + '_ {
+        let board = &self.state.board;
+        (0..N_ROWS).map(move |i| std::array::from_fn(|j| board.at(&Position(i, j)).into()))
+    }
+
+    /// Everything needed to draw a frame: the board, status, and score
+    pub fn snapshot_dto(&self) -> (dto::Frame, dto::Status, usize) {
+        let board = self
+            .state
+            .board
+            .to_dto()
+            .iter()
+            .map(|row| row.to_vec())
+            .collect();
+        (board, self.status, self.score)
+    }
+
+    /// The seed the game was built with, unknown when built from a board
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// The word position of the food rng stream, for restoring it with `set_rng_position`
+    pub fn rng_position(&self) -> u128 {
+        self.state.rng.get_word_pos()
+    }
+
+    pub fn set_rng_position(&mut self, word_position: u128) {
+        self.state.rng.set_word_pos(word_position);
+    }
+
+    /// Every direction applied so far, oldest first
+    pub fn directions(&self) -> &[Direction] {
+        &self.directions
+    }
+
+    /// The last `RECENT_DIRECTIONS` directions applied, oldest first
+    pub fn recent_directions(&self) -> &[Direction] {
+        let start = self
+            .directions
+            .len()
+            .saturating_sub(Self::RECENT_DIRECTIONS);
+        &self.directions[start..]
+    }
+
+    /// The snake from head to tail
+    pub fn snake_positions_vec(&self) -> Vec<dto::Position> {
+        self.state
+            .snake
+            .iter()
+            .map(|&position| position.into())
+            .collect()
+    }
+
+    pub fn snake_len(&self) -> usize {
+        self.state.snake.len()
+    }
+
+    /// Whether any position appears twice in the snake, which a valid game never allows
+    pub fn snake_self_intersects(&self) -> bool {
+        let positions = HashSet::<&Position>::from_iter(&self.state.snake);
+        positions.len() != self.state.snake.len()
+    }
+
+    /// Snake and obstacle cells packed row-major into `u64` words, bit `i * N_COLS + j` for
+    /// `(i, j)`
+    pub fn occupied_bitset(&self) -> Vec<u64> {
+        let mut words = vec![0; (N_ROWS * N_COLS).div_ceil(u64::BITS as usize)];
+        for (position, cell) in self.state.board.iter() {
+            if let Cell::Snake(_) | Cell::Obstacle = cell {
+                let index = Board::<N_ROWS, N_COLS>::to_index(&position);
+                words[index / u64::BITS as usize] |= 1 << (index % u64::BITS as usize);
+            }
+        }
+        words
+    }
+
+    /// How many foods are on the board right now
+    pub fn foods_on_board(&self) -> usize {
+        self.state.foods.len()
+    }
+
+    /// How many foods the board is kept topped up to, as set by `set_n_foods`
+    pub fn food_target(&self) -> usize {
+        self.n_foods
+    }
+
+    /// Changes how many foods are kept on the board, spawning any extra right away while there's
+    /// room and not replacing any surplus as it's eaten
+    pub fn set_n_foods(&mut self, n_foods: usize) {
+        self.n_foods = n_foods;
+        while self.state.foods.len() < self.n_foods && self.insert_food().is_ok() {}
+    }
+
+    /// Grows the snake into the empty cell ahead of its head without eating, which fails when
+    /// that cell isn't empty or the snake hasn't moved yet, and wins if the board fills up
+    pub fn feed(&mut self) -> Result<(), NoRoom> {
+        let direction = self.heading.ok_or(NoRoom)?;
+        let next_head = self.state.get_next_head(&direction).ok_or(NoRoom)?;
+        if let Cell::Empty(_) = self.state.board.at(&next_head) {
+            self.update_last_head(&direction);
+            self.insert_snake_head(next_head, Some(direction.opposite()));
+            self.set_status(self.state.check_is_won_status());
+            Ok(())
+        } else {
+            Err(NoRoom)
+        }
+    }
+
+    /// Whether the tail leaves its cell when moving in `direction`, which it won't when eating
+    /// or while growth is pending
+    pub fn tail_vacates_next(&self, direction: &Direction) -> bool {
+        let next_head = self.state.get_next_head(direction);
+        self.pending_growth == 0
+            && !matches!(
+                next_head.map(|next_head| self.state.board.at(&next_head)),
+                Some(Cell::Foods(_))
+            )
+    }
+
+    /// Directions that neither reverse the snake nor end the game, which rules out the tail
+    /// since moving into any snake cell loses
+    pub fn safe_moves(&self) -> Vec<Direction> {
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| Some(*direction) != self.forbidden_direction())
+            .filter(|direction| {
+                self.state
+                    .get_next_head(direction)
+                    .is_some_and(|next_head| {
+                        matches!(
+                            self.state.board.at(&next_head),
+                            Cell::Empty(_) | Cell::Foods(_)
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// The `2 * radius + 1` square of cells around `center`, wrapping across the edges the
+    /// topology wraps and showing the others as obstacles
+    pub fn window(&self, center: dto::Position, radius: usize) -> Vec<Vec<dto::Cell>> {
+        let topology = self.state.topology;
+        let axis = |center: usize, offset: isize, n: usize, wraps: bool| {
+            let index = center as isize + offset;
+            if wraps {
+                Some(index.rem_euclid(n as isize) as usize)
+            } else {
+                usize::try_from(index).ok().filter(|&index| index < n)
+            }
+        };
+        let radius = radius as isize;
+        (-radius..=radius)
+            .map(|di| {
+                let i = axis(center.0, di, N_ROWS, topology.wraps_vertically());
+                (-radius..=radius)
+                    .map(|dj| {
+                        let j = axis(center.1, dj, N_COLS, topology.wraps_horizontally());
+                        match (i, j) {
+                            (Some(i), Some(j)) => self.state.board.at(&Position(i, j)).into(),
+                            _ => dto::Cell::Obstacle,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Manhattan distance from the head to the closest food, wrapping across the edges the
+    /// topology wraps, or `None` without foods on the board
+    pub fn distance_to_nearest_food(&self) -> Option<usize> {
+        self.state
+            .foods
+            .iter()
+            .map(|food| {
+                let (di, dj) = self.offset_to(food);
+                di.unsigned_abs() + dj.unsigned_abs()
+            })
+            .min()
+    }
+
+    /// The food `distance_to_nearest_food` measures, ties going by the `TieBreak` option
+    pub fn nearest_food(&self) -> Option<dto::Position> {
+        let key = |food: &Position| {
+            let (di, dj) = self.offset_to(food);
+            let angle = (dj as f64).atan2(-di as f64).rem_euclid(TAU);
+            (di.unsigned_abs() + dj.unsigned_abs(), angle)
+        };
+        self.state
+            .foods
+            .iter()
+            .min_by(|a, b| {
+                let ((distance_a, angle_a), (distance_b, angle_b)) = (key(a), key(b));
+                distance_a
+                    .cmp(&distance_b)
+                    .then_with(|| match self.settings.tie_break {
+                        TieBreak::RowMajor => (a.0, a.1).cmp(&(b.0, b.1)),
+                        TieBreak::ColumnMajor => (a.1, a.0).cmp(&(b.1, b.0)),
+                        TieBreak::Clockwise => angle_a.total_cmp(&angle_b),
+                    })
+            })
+            .map(|food| (*food).into())
+    }
+
+    /// The shortest row and column steps from the head to `position` across the edges the
+    /// topology wraps
+    fn offset_to(&self, position: &Position) -> (isize, isize) {
+        let head = self.state.snake.front().expect("snake head");
+        let topology = self.state.topology;
+        let axis = |from: usize, to: usize, n: usize, wraps: bool| {
+            let offset = to as isize - from as isize;
+            let n = n as isize;
+            if wraps && offset.abs() * 2 > n {
+                offset - offset.signum() * n
+            } else {
+                offset
+            }
+        };
+        (
+            axis(head.0, position.0, N_ROWS, topology.wraps_vertically()),
+            axis(head.1, position.1, N_COLS, topology.wraps_horizontally()),
+        )
+    }
+
+    /// Free cells reachable by flood fill after moving one step in `direction`, or `0` when
+    /// that step runs into the snake, an obstacle, or an edge
+    pub fn free_space_ahead(&self, direction: &Direction) -> usize {
+        let Some(next_head) = self.state.get_next_head(direction) else {
+            return 0;
+        };
+        if let Cell::Snake(_) | Cell::Obstacle = self.state.board.at(&next_head) {
+            return 0;
+        }
+        let tail = self
+            .tail_vacates_next(direction)
+            .then(|| *self.get_next_tail());
+        let mut is_visited = [[false; N_COLS]; N_ROWS];
+        is_visited[next_head.0][next_head.1] = true;
+        let mut queue = VecDeque::from([next_head]);
+        let mut count = 0;
+        while let Some(position) = queue.pop_front() {
+            for direction in Direction::ALL {
+                let Some(neighbor) =
+                    self.state
+                        .board
+                        .move_in(&position, &direction, self.state.topology)
+                else {
+                    continue;
+                };
+                let is_free = match self.state.board.at(&neighbor) {
+                    Cell::Empty(_) | Cell::Foods(_) => true,
+                    Cell::Snake(_) => Some(neighbor) == tail,
+                    Cell::Obstacle => false,
+                };
+                if is_free && !is_visited[neighbor.0][neighbor.1] {
+                    is_visited[neighbor.0][neighbor.1] = true;
+                    count += 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        count
+    }
+
+    /// The most turns, up to `max_depth`, the snake can survive from here by exhaustive search,
+    /// ignoring growth from foods
+    #[cfg(feature = "analysis")]
+    pub fn longest_survivable_path(&self, max_depth: usize) -> usize {
+        let mut snake = self.state.snake.clone();
+        self.survivable_depth(&mut snake, max_depth)
+    }
+
+    #[cfg(feature = "analysis")]
+    fn survivable_depth(&self, snake: &mut VecDeque<Position>, depth_left: usize) -> usize {
+        let mut best = 0;
+        if depth_left == 0 {
+            return best;
+        }
+        let head = snake[0];
+        for direction in Direction::ALL {
+            let Some(next) = self
+                .state
+                .board
+                .move_in(&head, &direction, self.state.topology)
+            else {
+                continue;
+            };
+            if self.state.board.at(&next) == Cell::Obstacle || snake.contains(&next) {
+                continue;
+            }
+            let tail = snake.pop_back().expect("snake tail");
+            snake.push_front(next);
+            best = best.max(1 + self.survivable_depth(snake, depth_left - 1));
+            snake.pop_front();
+            snake.push_back(tail);
+            if best == depth_left {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Checks the invariants that `from_board` skips
+    pub fn validate(&self) -> Result<(), InvalidState> {
+        self.state.validate()
+    }
+
+    /// The cells of `other` that differ from `self` in row-major order
+    pub fn diff(&self, other: &GameState<N_ROWS, N_COLS>) -> Vec<(dto::Position, dto::Cell)> {
+        self.state
+            .board
+            .iter()
+            .filter_map(|(position, cell)| {
+                let new = dto::Cell::from(other.state.board.at(&position));
+                if dto::Cell::from(cell) == new {
+                    None
+                } else {
+                    Some((position.into(), new))
+                }
+            })
+            .collect()
+    }
+
+    pub fn char_grid(&self, theme: &CellTheme) -> [[char; N_COLS]; N_ROWS] {
+        let mut grid = [[theme.empty; N_COLS]; N_ROWS];
+        for (Position(i, j), cell) in self.state.board.iter() {
+            grid[i][j] = theme.glyph(cell.into());
+        }
+        grid
+    }
+
+    pub fn render_to_string(&self, theme: &CellTheme) -> String {
+        self.char_grid(theme)
+            .iter()
+            .map(String::from_iter)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Whether each cell has the kind `ascii` lays out, with rows parsed by
+    /// `dto::cells_from_str` and snake paths ignored
+    pub fn matches_ascii(&self, ascii: &str) -> bool {
+        let rows = Vec::from_iter(
+            ascii
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(dto::cells_from_str),
+        );
+        rows.len() == N_ROWS
+            && rows
+                .iter()
+                .zip(self.board_rows())
+                .all(|(expected, actual)| {
+                    expected.len() == N_COLS
+                        && expected.iter().zip(actual).all(|(expected, actual)| {
+                            mem::discriminant(expected) == mem::discriminant(&actual)
+                        })
+                })
+    }
+
+    /// Panics with the expected and actual boards side by side unless `matches_ascii`
+    pub fn assert_matches_ascii(&self, ascii: &str) {
+        if self.matches_ascii(ascii) {
+            return;
+        }
+        let expected = Vec::from_iter(ascii.lines().map(str::trim).filter(|line| !line.is_empty()));
+        let actual = self.char_grid(&ASCII_THEME);
+        let n_lines = expected.len().max(N_ROWS);
+        let side_by_side = (0..n_lines)
+            .map(|i| {
+                let expected = expected.get(i).copied().unwrap_or_default();
+                let actual = actual.get(i).map(String::from_iter).unwrap_or_default();
+                format!("{expected:<N_COLS$} | {actual}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        panic!("board mismatch (expected | actual)\n{side_by_side}");
+    }
+
+    /// The board in ASCII with row and column numbers and the head as `@`, followed by the
+    /// score, status, and snake length
+    pub fn pretty_debug(&self) -> String {
+        let mut grid = self.char_grid(&ASCII_THEME);
+        let head = self.state.snake.front().expect("snake head");
+        grid[head.0][head.1] = '@';
+        let header = (0..N_COLS).map(|j| char::from_digit((j % 10) as u32, 10).unwrap());
+        let mut lines = vec![format!("  {}", String::from_iter(header))];
+        lines.extend(
+            grid.iter()
+                .enumerate()
+                .map(|(i, row)| format!("{} {}", i % 10, String::from_iter(row))),
+        );
+        lines.push(format!(
+            "score {}, status {:?}, length {}",
+            self.score,
+            self.status,
+            self.snake_len()
+        ));
+        lines.join("\n")
+    }
+
+    /// Rebuilds the starting board from `seed` with the same settings and the current food
+    /// target, so the same seed always replays an identical game, leaving the game as is when
+    /// those don't make a valid game
+    pub fn reset_with_seed(&mut self, seed: u64) -> Result<(), InvalidOptions> {
+        let mut settings = self.settings.clone();
+        settings.n_foods = self.n_foods;
+        let options = Options {
+            seeder: Box::new(MockSeeder(seed)),
+            settings,
+        };
+        options.validate()?;
+        options.init_game_state(self, Board::default());
+        options.add_foods(self);
+        self.send_board();
+        Ok(())
+    }
+
+    /// Sends every cell to the view, once the game is built, leaving no changes before the first
+    /// turn
+    fn send_board(&mut self) {
+        for (position, _) in self.state.board.iter().collect::<Vec<_>>() {
+            self.cell_updated(position);
+        }
+        self.changes.clear();
+    }
+
+    fn tick_food_spawn(&mut self) {
+        if self.ticks_until_next_food().is_some() {
+            self.spawn_countdown -= 1;
+            if self.spawn_countdown == 0 {
+                self.spawn_food();
+                self.spawn_countdown = self.settings.food_spawn_delay;
+            }
         }
     }
 
@@ -80,12 +827,29 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
         self.view.swap_cell(&position.into(), cell.into());
     }
 
+    /// Records a change from `old` to the current cell, keeping the earliest `old` when a
+    /// position changes twice in a turn, and notifies the view
+    fn swap_cell(&mut self, position: Position, old: dto::Cell) {
+        let new = self.state.board.at(&position).into();
+        let position = dto::Position::from(position);
+        match self
+            .changes
+            .iter_mut()
+            .find(|(other, _, _)| *other == position)
+        {
+            Some(change) => change.2 = new,
+            None => self.changes.push((position, old, new)),
+        }
+        self.view.swap_cell(&position, new);
+    }
+
     fn get_next_tail(&self) -> &Position {
         self.state.snake.back().expect("non empty snake next tail")
     }
 
     fn update_next_tail(&mut self) {
         let next_tail = *self.get_next_tail();
+        let old = self.state.board.at(&next_tail).into();
         *self.state.board.at_mut(&next_tail) =
             if let Cell::Snake(path) = self.state.board.at(&next_tail) {
                 Cell::Snake(Path {
@@ -95,11 +859,11 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
             } else {
                 panic!("invariant not snake {:?}", self.state.board.at(&next_tail))
             };
-        let new = dto::Cell::from(self.state.board.at(&next_tail));
-        self.view.swap_cell(&next_tail.into(), new);
+        self.swap_cell(next_tail, old);
     }
 
     fn insert_snake_head(&mut self, next_head: Position, entry: Option<Direction>) {
+        let old = self.state.board.at(&next_head).into();
         match self.state.board.at(&next_head) {
             Cell::Empty(empty_index) => self.remove_empty(&next_head, empty_index),
             Cell::Foods(foods_index) => self.remove_foods(&next_head, foods_index),
@@ -107,8 +871,7 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
         }
         *self.state.board.at_mut(&next_head) = Cell::Snake(Path { entry, exit: None });
         self.state.snake.push_front(next_head);
-        let new = dto::Cell::from(self.state.board.at(&next_head));
-        self.view.swap_cell(&next_head.into(), new);
+        self.swap_cell(next_head, old);
     }
 
     fn remove_empty(&mut self, next_head: &Position, empty_index: usize) {
@@ -127,12 +890,23 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
         }
     }
 
+    fn seam_crossed(&mut self, last_head: Position, next_head: Position, direction: &Direction) {
+        let velocity = direction.as_velocity();
+        let i = last_head.0 as isize + velocity.0;
+        let j = last_head.1 as isize + velocity.1;
+        let is_wrapped = !(0..N_ROWS as isize).contains(&i) || !(0..N_COLS as isize).contains(&j);
+        if is_wrapped {
+            self.view.seam(&last_head.into(), &next_head.into());
+        }
+    }
+
     fn get_last_head(&self) -> &Position {
         self.state.snake.front().expect("non empty snake last head")
     }
 
     fn update_last_head(&mut self, direction: &Direction) {
         let last_head = *self.get_last_head();
+        let old = self.state.board.at(&last_head).into();
         *self.state.board.at_mut(&last_head) =
             if let Cell::Snake(Path { entry, exit: None }) = self.state.board.at(&last_head) {
                 Cell::Snake(Path {
@@ -145,15 +919,48 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
                     self.state.board.at(&last_head)
                 )
             };
-        let new = dto::Cell::from(self.state.board.at(&last_head));
-        self.view.swap_cell(&last_head.into(), new);
+        self.swap_cell(last_head, old);
+    }
+
+    /// The `empty` index of the next food in the sequence, or `None` without a sequence
+    fn next_sequenced_food(&mut self) -> Result<Option<usize>, FoodSequenceError> {
+        let Some(food_sequence) = self.settings.food_sequence.as_ref() else {
+            return Ok(None);
+        };
+        let position = *food_sequence
+            .get(self.n_sequenced_foods)
+            .ok_or(FoodSequenceError::Exhausted)?;
+        match self.state.board.at(&position.into()) {
+            Cell::Empty(empty_index) => {
+                self.n_sequenced_foods += 1;
+                Ok(Some(empty_index))
+            }
+            _ => Err(FoodSequenceError::Occupied(position)),
+        }
+    }
+
+    /// Inserts a food during a turn, keeping any food sequence error for `try_iterate_turn`
+    fn spawn_food(&mut self) {
+        if let Err(NoFood::FoodSequence(error)) = self.insert_food() {
+            self.food_sequence_error = Some(error);
+        }
     }
 
-    fn insert_food(&mut self) -> Result<(), MaxFoods> {
+    fn insert_food(&mut self) -> Result<(), NoFood> {
+        let position = self.place_food()?;
+        self.swap_cell(position, dto::Cell::Empty);
+        Ok(())
+    }
+
+    /// Places a food without notifying the view, for building the game
+    fn place_food(&mut self) -> Result<Position, NoFood> {
         if self.state.empty.is_empty() {
-            Err(MaxFoods)
+            Err(NoFood::MaxFoods)
         } else {
-            let empty_index = self.state.rng.gen_range(0..self.state.empty.len());
+            let empty_index = match self.next_sequenced_food().map_err(NoFood::FoodSequence)? {
+                Some(empty_index) => empty_index,
+                None => self.state.rng.gen_range(0..self.state.empty.len()),
+            };
             let position = self.state.empty.swap_remove(empty_index);
             if empty_index < self.state.empty.len() {
                 let position = self.state.empty[empty_index];
@@ -162,22 +969,26 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS>
             let foods_index = self.state.foods.len();
             *self.state.board.at_mut(&position) = Cell::Foods(foods_index);
             self.state.foods.push(position);
-            self.view.swap_cell(&position.into(), dto::Cell::Foods);
-            Ok(())
+            Ok(position)
         }
     }
 }
 
+impl<'a, const N_ROWS: usize, const N_COLS: usize> fmt::Display for GameState<'a, N_ROWS, N_COLS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render_to_string(&CellTheme::default()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::VecDeque;
 
-    use rand::SeedableRng;
-
     use crate::{
-        controller::mock_controller::MockController,
+        controller::mock_controller::{MockController, StrictMockController},
+        game_state::{harness::GameHarness, Topology},
         seeder::{MockSeeder, Seeder},
-        view::MockView,
+        view::{BufferedView, MockView},
     };
 
     use super::*;
@@ -207,6 +1018,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn try_from_board_headless() {
+        let board = Board::new([[Cell::Empty(0), Cell::Empty(1)]]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = ChaCha8Rng::seed_from_u64(0);
+        let game_state = GameState::try_from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.unwrap_err(), NoSnakeHead);
+    }
+
+    #[test]
+    fn try_from_board_misindexed() {
+        let head = Cell::Snake(Path {
+            entry: None,
+            exit: None,
+        });
+        let board = Board::new([[head, Cell::Empty(5), Cell::Empty(1)]]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = ChaCha8Rng::seed_from_u64(0);
+        let game_state = GameState::try_from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(
+            game_state.unwrap().state.validate(),
+            Err(InvalidState::Empty)
+        );
+    }
+
     #[test]
     pub fn from_board() {
         let board = Board::new([[Cell::Snake(Path {
@@ -222,42 +1060,606 @@ mod tests {
     }
 
     #[test]
-    pub fn get_last_head() {
-        let options = Options::<3, 3>::with_seed(1, 0);
-        let mut controller = MockController(Direction::Right);
-        let mut view = MockView::default();
-        let game_state = options.build(&mut controller, &mut view).unwrap();
-        assert_eq!(*game_state.get_last_head(), Position(1, 1));
+    fn iterate_turn_on_game_over() {
+        let mut harness =
+            GameHarness::<3, 3>::new().settings(|settings| settings.topology = Topology::Bounded);
+        let mut game_state = harness.build();
+        game_state.iterate_turn();
+        game_state.iterate_turn();
+        game_state.iterate_turn();
+        drop(game_state);
+        assert_eq!(harness.view.1, [false]);
     }
 
     #[test]
-    fn iterate_turn_empty() {
-        let mut controller = MockController(Direction::Right);
+    fn on_game_over_once() {
+        let mut harness =
+            GameHarness::<3, 3>::new().settings(|settings| settings.topology = Topology::Bounded);
+        let mut game_state = harness.build();
+        for _ in 0..4 {
+            game_state.iterate_turn();
+        }
+        for direction in [Direction::Up, Direction::Left, Direction::Down] {
+            game_state.iterate_turn_with(direction);
+        }
+        drop(game_state);
+        assert_eq!(harness.view.1, [false]);
+
+        let mut harness = GameHarness::<1, 2>::new().n_foods(1);
+        let mut game_state = harness.build();
+        for _ in 0..4 {
+            game_state.iterate_turn();
+        }
+        drop(game_state);
+        assert_eq!(harness.view.1, [true]);
+    }
+
+    #[test]
+    fn iterate_turn_after_loss() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .settings(|settings| settings.topology = Topology::Bounded)
+            .directions([Direction::Up, Direction::Up]);
+        let mut game_state = harness.build();
+        game_state.iterate_turn();
+        let lost = dto::Status::Over { is_won: false };
+        assert_eq!(game_state.iterate_turn(), lost);
+        let snake = game_state.snake_positions_vec();
+        assert_eq!(game_state.iterate_turn(), lost);
+        assert_eq!(game_state.iterate_turn_with(Direction::Down), lost);
+        assert_eq!(game_state.try_iterate_turn(), Ok(lost));
+        assert_eq!(game_state.status(), lost);
+        assert_eq!(game_state.snake_positions_vec(), snake);
+        assert_eq!(game_state.directions(), [Direction::Up, Direction::Up]);
+    }
+
+    #[test]
+    fn iterate_turn_counts_over_polls() {
+        let mut controller = StrictMockController::new([Direction::Up]);
         let mut view = MockView::default();
         let mut game_state = Options::<3, 3>::with_seed(0, 0)
+            .with_topology(Topology::Bounded)
             .build(&mut controller, &mut view)
             .unwrap();
         assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
-        game_state.assert_is_empty(&Position(1, 1), 4);
-        game_state.assert_is_snake_with_path(
-            &Position(1, 2),
-            Path {
-                entry: None,
-                exit: None,
-            },
-        );
+        assert_eq!(game_state.snake_positions_vec(), [(0, 1)]);
+        let lost = dto::Status::Over { is_won: false };
+        assert_eq!(game_state.iterate_turn(), lost);
+        assert_eq!(game_state.iterate_turn(), lost);
+        assert_eq!(game_state.directions(), [Direction::Up, Direction::Up]);
+        assert_eq!(controller.overflow(), 1);
     }
 
     #[test]
-    fn iterate_turn_foods() {
-        let new_foods_position = Position(1, 2);
-        let mut controller = MockController(Direction::Down);
+    fn snake_self_intersects() {
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = setup_loosable_board(&mut controller, &mut view);
+        assert!(!game_state.snake_self_intersects());
+        let head = game_state.state.snake[0];
+        game_state.state.snake.push_back(head);
+        assert!(game_state.snake_self_intersects());
+    }
+
+    #[test]
+    fn from_parts() {
+        let board = Board::new([[
+            Cell::Snake(Path {
+                entry: None,
+                exit: None,
+            }),
+            Cell::Foods(0),
+            Cell::Empty(0),
+        ]]);
+        let state = State {
+            board,
+            empty: vec![Position(0, 2)],
+            foods: vec![Position(0, 1)],
+            snake: VecDeque::from([Position(0, 0)]),
+            rng: MockSeeder(0).get_rng(),
+            topology: Topology::default(),
+        };
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = GameState::from_parts(state, &mut controller, &mut view);
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(
+            game_state.state.snake,
+            VecDeque::from([Position(0, 1), Position(0, 0)])
+        );
+        assert_eq!(game_state.state.foods, [Position(0, 2)]);
+    }
+
+    #[test]
+    pub fn get_last_head() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let game_state = harness.build();
+        assert_eq!(*game_state.get_last_head(), Position(1, 1));
+    }
+
+    #[test]
+    fn iterate_turn_empty() {
+        let mut harness = GameHarness::<3, 3>::new();
+        let mut game_state = harness.build();
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        game_state.assert_is_empty(&Position(1, 1), 4);
+        game_state.assert_is_snake_with_path(
+            &Position(1, 2),
+            Path {
+                entry: None,
+                exit: None,
+            },
+        );
+    }
+
+    #[test]
+    fn iterate_turn_length_one_vacates_tail() {
+        let board = Board::new([[Cell::head(None), Cell::Empty(0), Cell::Empty(1)]]);
+        let mut controller = MockController(Direction::Right);
         let mut view = MockView::default();
-        let mut game_state = Options::<3, 3>::with_seed(3, 0)
+        let rng = MockSeeder(0).get_rng();
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.validate(), Ok(()));
+        game_state.assert_is_empty(&Position(0, 0), 0);
+        game_state.assert_is_empty(&Position(0, 2), 1);
+        game_state.assert_is_snake_with_path(
+            &Position(0, 1),
+            Path {
+                entry: None,
+                exit: None,
+            },
+        );
+        assert_eq!(
+            game_state.last_changes(),
+            [
+                ((0, 0), snake(None, None), dto::Cell::Empty),
+                ((0, 1), dto::Cell::Empty, snake(None, None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn forbidden_direction() {
+        let mut harness = GameHarness::<1, 4>::new().n_foods(1);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.forbidden_direction(), None);
+        while game_state.snake_len() == 1 {
+            game_state.iterate_turn();
+        }
+        assert_eq!(game_state.forbidden_direction(), Some(Direction::Left));
+    }
+
+    #[test]
+    fn safe_moves() {
+        use dto::Cell::Empty as E;
+        use Direction::*;
+        let board = Board::from([
+            [
+                snake(Some(Right), None),
+                snake(Some(Right), Some(Left)),
+                snake(Some(Down), Some(Left)),
+            ],
+            [E, E, snake(None, Some(Up))],
+        ]);
+        let cases = [
+            (Topology::Bounded, vec![Down]),
+            (Topology::Torus, vec![Up, Down]),
+        ];
+        for (topology, expected) in cases {
+            let mut controller = MockController(Direction::Right);
+            let mut view = MockView::default();
+            let rng = MockSeeder(0).get_rng();
+            let mut game_state =
+                GameState::from_board(board.clone(), &mut controller, &mut view, rng);
+            game_state.state.topology = topology;
+            let safe_moves = game_state.safe_moves();
+            assert_eq!(safe_moves, expected);
+            for direction in safe_moves {
+                let mut controller = MockController(Direction::Right);
+                let mut view = MockView::default();
+                let rng = MockSeeder(0).get_rng();
+                let mut game_state =
+                    GameState::from_board(board.clone(), &mut controller, &mut view, rng);
+                game_state.state.topology = topology;
+                assert_eq!(
+                    game_state.iterate_turn_with(direction),
+                    dto::Status::Ongoing
+                );
+            }
+        }
+
+        let board = Board::from([
+            [snake(Some(Right), None), snake(Some(Down), Some(Left))],
+            [snake(None, Some(Right)), snake(Some(Left), Some(Up))],
+        ]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = MockSeeder(0).get_rng();
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        game_state.state.topology = Topology::Bounded;
+        assert_eq!(game_state.safe_moves(), []);
+    }
+
+    #[test]
+    fn recent_directions() {
+        use Direction::*;
+        let mut harness = GameHarness::<3, 3>::new().directions([Up, Right, Down, Down, Left]);
+        let mut game_state = harness.build();
+        game_state.iterate_turn();
+        assert_eq!(game_state.recent_directions(), [Up]);
+        for _ in 0..4 {
+            game_state.iterate_turn();
+        }
+        assert_eq!(game_state.recent_directions(), [Right, Down, Down, Left]);
+    }
+
+    #[test]
+    fn pending_growth() {
+        let mut harness = GameHarness::<1, 4>::new()
+            .n_foods(1)
+            .settings(|settings| settings.growth_per_food = 3);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.pending_growth(), 0);
+        while game_state.snake_len() == 1 {
+            game_state.iterate_turn();
+        }
+        assert_eq!(game_state.pending_growth(), 2);
+        game_state.iterate_turn();
+        assert_eq!(game_state.pending_growth(), 1);
+    }
+
+    #[test]
+    fn wraps() {
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = Options::<3, 3>::with_seed(1, 0)
+            .build(&mut controller, &mut view)
+            .unwrap();
+        assert!(game_state.wraps());
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(1)
+            .settings(|settings| settings.topology = Topology::Bounded);
+        let game_state = harness.build();
+        assert!(!game_state.wraps());
+    }
+
+    #[test]
+    fn apply_key() {
+        let mut harness =
+            GameHarness::<3, 3>::new().settings(|settings| settings.topology = Topology::Bounded);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.apply_key('w'), Ok(dto::Status::Ongoing));
+        assert_eq!(game_state.heading(), Some(Direction::Up));
+        assert_eq!(game_state.snake_positions_vec(), [(0, 1)]);
+        assert_eq!(game_state.apply_key('x'), Err(dto::InvalidDirection));
+        assert_eq!(game_state.directions(), [Direction::Up]);
+        assert_eq!(
+            game_state.apply_key('w'),
+            Ok(dto::Status::Over { is_won: false })
+        );
+    }
+
+    #[test]
+    fn window() {
+        let mut harness = GameHarness::<5, 5>::new();
+        let mut game_state = harness.build();
+        let head = snake(None, None);
+        let window = game_state.window((2, 2), 1);
+        assert_eq!(window.len(), 3);
+        assert!(window.iter().all(|row| row.len() == 3));
+        assert_eq!(window[1][1], head);
+        assert_eq!(game_state.window((0, 0), 2)[4][4], head);
+        game_state.state.topology = Topology::Bounded;
+        let window = game_state.window((0, 0), 1);
+        assert_eq!(window[0], [dto::Cell::Obstacle; 3]);
+        assert_eq!(window[1][0], dto::Cell::Obstacle);
+        assert_eq!(window[1][1], dto::Cell::Empty);
+    }
+
+    #[test]
+    fn head_path() {
+        let mut harness = GameHarness::<1, 4>::new().n_foods(1);
+        let mut game_state = harness.build();
+        while game_state.snake_len() == 1 {
+            game_state.iterate_turn();
+        }
+        assert_eq!(
+            game_state.head_path(),
+            dto::Path {
+                entry: Some(Direction::Left),
+                exit: None,
+            }
+        );
+    }
+
+    #[test]
+    fn cells_changed_last_turn() {
+        let mut harness = GameHarness::<3, 3>::new();
+        let mut game_state = harness.build();
+        assert_eq!(game_state.cells_changed_last_turn(), 0);
+        game_state.iterate_turn();
+        assert_eq!(game_state.cells_changed_last_turn(), 2);
+    }
+
+    #[test]
+    fn cells_changed_last_turn_after_build() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(2)
+            .settings(|settings| settings.obstacles = vec![(2, 0)]);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.cells_changed_last_turn(), 0);
+        game_state.iterate_turn();
+        game_state.reset_with_seed(1).unwrap();
+        assert_eq!(game_state.cells_changed_last_turn(), 0);
+    }
+
+    #[test]
+    fn last_changes() {
+        let board = Board::new([[
+            Cell::tail(Some(Direction::Right)),
+            Cell::head(Some(Direction::Left)),
+            Cell::Empty(0),
+            Cell::Empty(1),
+        ]]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = ChaCha8Rng::seed_from_u64(0);
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(
+            game_state.last_changes(),
+            [
+                (
+                    (0, 0),
+                    snake(None, Some(Direction::Right)),
+                    dto::Cell::Empty
+                ),
+                (
+                    (0, 1),
+                    snake(Some(Direction::Left), None),
+                    snake(None, Some(Direction::Right))
+                ),
+                ((0, 2), dto::Cell::Empty, snake(Some(Direction::Left), None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn last_changes_after_build() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(2).settings(|settings| {
+            settings.first_food = Some(dto::Direction::Up);
+            settings.obstacles = vec![(2, 2)];
+        });
+        let mut game_state = harness.build();
+        assert_eq!(game_state.last_changes(), []);
+        game_state.iterate_turn();
+        assert!(!game_state.last_changes().is_empty());
+        game_state.reset_with_seed(1).unwrap();
+        assert_eq!(game_state.last_changes(), []);
+    }
+
+    #[test]
+    fn iterate_turn_with_ignores_controller() {
+        let mut harness = GameHarness::<3, 3>::new();
+        let mut game_state = harness.build();
+        assert_eq!(
+            game_state.iterate_turn_with(Direction::Up),
+            dto::Status::Ongoing
+        );
+        assert_eq!(game_state.directions(), [Direction::Up]);
+        assert_eq!(*game_state.get_last_head(), Position(0, 1));
+    }
+
+    #[test]
+    fn iterate_turn_polls_controller() {
+        let mut harness = GameHarness::<3, 3>::new().direction(Direction::Left);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.directions(), [Direction::Left]);
+        assert_eq!(*game_state.get_last_head(), Position(1, 0));
+    }
+
+    #[test]
+    fn iterate_turn_seam() {
+        let mut controller = MockController(Direction::Up);
+        let mut view = BufferedView::default();
+        let mut game_state = Options::<3, 3>::with_seed(0, 0)
             .build(&mut controller, &mut view)
             .unwrap();
+        game_state.iterate_turn();
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        drop(game_state);
+        assert_eq!(view.flush_seams(), [((0, 1), (2, 1))]);
+    }
+
+    #[test]
+    fn iterate_turn_growth_per_food() {
+        let mut harness = GameHarness::<1, 5>::new()
+            .n_foods(1)
+            .settings(|settings| settings.growth_per_food = 2);
+        let mut game_state = harness.build();
+        let mut status = dto::Status::Ongoing;
+        while game_state.score() == 0 {
+            status = game_state.iterate_turn();
+        }
+        assert_eq!(status, dto::Status::Ongoing);
+        assert_eq!(game_state.snake_len(), 2);
+        game_state.iterate_turn();
+        assert_eq!(game_state.snake_len(), 3);
+        assert_eq!(game_state.pending_growth, 0);
+        assert_eq!(game_state.validate(), Ok(()));
+    }
+
+    #[test]
+    fn iterate_turn_growth_per_food_fills_board() {
+        let mut harness = GameHarness::<1, 2>::new()
+            .n_foods(1)
+            .settings(|settings| settings.growth_per_food = 2);
+        let mut game_state = harness.build();
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: true }
+        );
+
+        let mut harness = GameHarness::<1, 4>::new()
+            .n_foods(1)
+            .settings(|settings| settings.growth_per_food = 2);
+        let mut game_state = harness.build();
+        let mut status = dto::Status::Ongoing;
+        for _ in 0..8 {
+            status = game_state.iterate_turn();
+            if status != dto::Status::Ongoing {
+                break;
+            }
+        }
+        assert_eq!(status, dto::Status::Over { is_won: true });
+        assert_eq!(game_state.snake_len(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "crc32")]
+    fn board_crc32() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        let checksum = game_state.board_crc32();
+        assert_eq!(game_state.board_crc32(), checksum);
+        game_state.iterate_turn();
+        assert_ne!(game_state.board_crc32(), checksum);
+    }
+
+    #[test]
+    #[cfg(feature = "ndarray")]
+    fn to_array2() {
+        let mut harness = GameHarness::<3, 4>::new()
+            .n_foods(1)
+            .settings(|settings| settings.obstacles = vec![(0, 3)]);
+        let game_state = harness.build();
+        let array = game_state.to_array2();
+        assert_eq!(array.dim(), (3, 4));
+        assert_eq!(array[(1, 2)], 2);
+        assert_eq!(array[(0, 3)], 3);
+        assert_eq!(array.iter().filter(|&&code| code == 1).count(), 1);
+    }
+
+    #[test]
+    fn iterate_turn_bounded() {
+        let mut harness =
+            GameHarness::<3, 3>::new().settings(|settings| settings.topology = Topology::Bounded);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: false }
+        );
+        assert_eq!(game_state.status(), dto::Status::Over { is_won: false });
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn longest_survivable_path() {
+        let board = Board::new([[
+            Cell::tail(Some(Direction::Right)),
+            Cell::head(Some(Direction::Left)),
+            Cell::Empty(0),
+        ]]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = ChaCha8Rng::seed_from_u64(0);
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.longest_survivable_path(5), 5);
+        game_state.state.topology = Topology::Bounded;
+        assert_eq!(game_state.longest_survivable_path(5), 1);
+    }
+
+    #[test]
+    fn reset_with_seed() {
+        let mut harness = GameHarness::<5, 5>::new()
+            .n_foods(1)
+            .settings(|settings| settings.obstacles = vec![(0, 0)]);
+        let mut game_state = harness.build();
+        game_state.iterate_turn();
+        assert_eq!(game_state.reset_with_seed(7), Ok(()));
+        let foods = game_state.state.foods.clone();
+        assert_eq!(game_state.seed(), Some(7));
+        assert_eq!(game_state.directions(), []);
+        assert_eq!(game_state.snake_len(), 1);
+        assert_eq!(game_state.state.board.at(&Position(0, 0)), Cell::Obstacle);
+        assert_eq!(game_state.validate(), Ok(()));
+        game_state.iterate_turn();
+        assert_eq!(game_state.reset_with_seed(7), Ok(()));
+        assert_eq!(game_state.state.foods, foods);
+    }
+
+    #[test]
+    fn reset_with_seed_sends_cells_once() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(2);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.reset_with_seed(7), Ok(()));
+        assert_eq!(harness.view.0.len(), 9 + 9);
+        let positions =
+            HashSet::<_>::from_iter(harness.view.0[9..].iter().map(|(position, _)| position));
+        assert_eq!(positions.len(), 9);
+    }
+
+    #[test]
+    fn reset_with_seed_no_room() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        game_state.set_n_foods(9);
+        let foods = game_state.state.foods.clone();
+        assert_eq!(
+            game_state.reset_with_seed(0),
+            Err(InvalidOptions::NotEnoughRoom)
+        );
+        assert_eq!(game_state.state.foods, foods);
+        game_state.set_n_foods(2);
+        assert_eq!(game_state.reset_with_seed(0), Ok(()));
+        assert_eq!(game_state.foods_on_board(), 2);
+    }
+
+    #[test]
+    fn reset_with_seed_first_food_blocked() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(1)
+            .settings(|settings| settings.first_food = Some(Direction::Up));
+        let mut game_state = harness.build();
+        game_state.set_n_foods(0);
+        assert_eq!(
+            game_state.reset_with_seed(0),
+            Err(InvalidOptions::FirstFoodBlocked)
+        );
+    }
+
+    #[test]
+    fn ticks_until_next_food() {
+        let mut harness =
+            GameHarness::<5, 5>::new().settings(|settings| settings.food_spawn_delay = 3);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.ticks_until_next_food(), None);
+        game_state.n_foods = 1;
+        assert_eq!(game_state.ticks_until_next_food(), Some(3));
+        game_state.iterate_turn();
+        assert_eq!(game_state.ticks_until_next_food(), Some(2));
+        game_state.iterate_turn();
+        assert_eq!(game_state.ticks_until_next_food(), Some(1));
+        game_state.iterate_turn();
+        assert_eq!(game_state.state.foods.len(), 1);
+        assert_eq!(game_state.ticks_until_next_food(), None);
+        game_state.n_foods = 2;
+        assert_eq!(game_state.ticks_until_next_food(), Some(3));
+    }
+
+    #[test]
+    fn iterate_turn_foods() {
+        let new_foods_position = Position(1, 2);
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(3)
+            .direction(Direction::Down);
+        let mut game_state = harness.build();
         game_state.assert_is_empty(&new_foods_position, 4);
         assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.score(), 1);
+        assert_eq!(game_state.foods_on_board(), game_state.food_target());
         game_state.assert_is_snake_with_path(
             &Position(1, 1),
             Path {
@@ -288,6 +1690,374 @@ mod tests {
         );
     }
 
+    #[test]
+    fn diff() {
+        let options = Options::<3, 3>::with_seed(1, 0);
+        let mut controller_a = MockController(Direction::Right);
+        let mut view_a = MockView::default();
+        let game_state_a = options.build(&mut controller_a, &mut view_a).unwrap();
+        let mut controller_b = MockController(Direction::Right);
+        let mut view_b = MockView::default();
+        let mut game_state_b = options.build(&mut controller_b, &mut view_b).unwrap();
+        assert_eq!(game_state_a.diff(&game_state_b), []);
+        game_state_b.iterate_turn();
+        let path = Path {
+            entry: None,
+            exit: None,
+        };
+        assert_eq!(
+            game_state_a.diff(&game_state_b),
+            [((1, 1), dto::Cell::Empty), ((1, 2), dto::Cell::Snake(path))]
+        );
+    }
+
+    #[test]
+    fn set_n_foods_increase() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        game_state.set_n_foods(3);
+        assert_eq!(game_state.state.foods.len(), 3);
+        game_state.set_n_foods(9);
+        assert_eq!(game_state.state.foods.len(), 8);
+    }
+
+    #[test]
+    fn set_n_foods_decrease() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(3)
+            .direction(Direction::Down);
+        let mut game_state = harness.build();
+        game_state.set_n_foods(1);
+        assert_eq!(game_state.state.foods.len(), 3);
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.state.foods.len(), 2);
+    }
+
+    #[test]
+    fn snake_positions_vec() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let game_state = harness.build();
+        assert_eq!(game_state.snake_positions_vec(), [(1, 1)]);
+    }
+
+    #[test]
+    fn is_board_full() {
+        let mut harness = GameHarness::<1, 2>::new().n_foods(1);
+        let mut game_state = harness.build();
+        assert!(!game_state.is_board_full());
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: true }
+        );
+        assert!(game_state.is_board_full());
+    }
+
+    #[test]
+    fn board_rows() {
+        let mut harness = GameHarness::<2, 3>::new().n_foods(2);
+        let game_state = harness.build();
+        let rows = Vec::from_iter(game_state.board_rows());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows, game_state.state.board.to_dto());
+    }
+
+    #[test]
+    fn snapshot_dto() {
+        let mut harness = GameHarness::<1, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: true }
+        );
+        let head = dto::Cell::Snake(dto::Path {
+            entry: Some(Direction::Left),
+            exit: None,
+        });
+        let (board, status, score) = game_state.snapshot_dto();
+        assert_eq!(board.len(), 1);
+        assert_eq!(board[0].len(), 3);
+        assert_eq!(board[0].iter().filter(|&&cell| cell == head).count(), 1);
+        assert!(board[0]
+            .iter()
+            .all(|cell| matches!(cell, dto::Cell::Snake(_))));
+        assert_eq!(status, dto::Status::Over { is_won: true });
+        assert_eq!(score, 2);
+    }
+
+    #[test]
+    fn occupied_bitset() {
+        let mut harness = GameHarness::<9, 9>::new().n_foods(3);
+        let game_state = harness.build();
+        let bitset = game_state.occupied_bitset();
+        let popcount: u32 = bitset.iter().map(|word| word.count_ones()).sum();
+        assert_eq!(popcount as usize, game_state.snake_len());
+        assert_eq!(bitset, [1 << (4 * 9 + 4), 0]);
+    }
+
+    #[test]
+    fn set_rng_position() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        let rng_position = game_state.rng_position();
+        let expected: [u64; 4] = game_state.state.rng.gen();
+        assert_ne!(game_state.rng_position(), rng_position);
+        game_state.set_rng_position(rng_position);
+        let actual: [u64; 4] = game_state.state.rng.gen();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn feed() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.feed(), Err(NoRoom));
+        game_state.iterate_turn();
+        assert_eq!(game_state.snake_len(), 1);
+        assert_eq!(game_state.feed(), Ok(()));
+        assert_eq!(game_state.snake_len(), 2);
+        assert_eq!(game_state.validate(), Ok(()));
+        game_state.assert_is_snake_with_path(
+            &Position(1, 0),
+            Path {
+                entry: Some(Direction::Left),
+                exit: None,
+            },
+        );
+    }
+
+    #[test]
+    fn feed_fills_board() {
+        let mut harness = GameHarness::<1, 2>::new();
+        let mut game_state = harness.build();
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.feed(), Ok(()));
+        assert_eq!(game_state.status(), dto::Status::Over { is_won: true });
+        assert_eq!(harness.view.1, [true]);
+    }
+
+    #[test]
+    fn tail_vacates_next_empty() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(3);
+        let game_state = harness.build();
+        assert!(game_state.tail_vacates_next(&Direction::Right));
+    }
+
+    #[test]
+    fn tail_vacates_next_foods() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(3)
+            .direction(Direction::Down);
+        let game_state = harness.build();
+        assert!(!game_state.tail_vacates_next(&Direction::Down));
+    }
+
+    #[test]
+    fn tail_vacates_next_pending_growth() {
+        let mut harness = GameHarness::<1, 6>::new()
+            .n_foods(1)
+            .settings(|settings| settings.growth_per_food = 3);
+        let mut game_state = harness.build();
+        while game_state.snake_len() == 1 {
+            game_state.iterate_turn();
+        }
+        assert_eq!(game_state.pending_growth(), 2);
+        assert!(!game_state.tail_vacates_next(&Direction::Right));
+        let tail = *game_state.get_next_tail();
+        game_state.iterate_turn();
+        assert_eq!(*game_state.get_next_tail(), tail);
+    }
+
+    const fn snake(entry: Option<Direction>, exit: Option<Direction>) -> dto::Cell {
+        dto::Cell::Snake(Path { entry, exit })
+    }
+
+    const POCKET_BOARD: [[dto::Cell; 5]; 5] = {
+        use dto::Cell::Empty as E;
+        use Direction::*;
+        [
+            [
+                snake(Some(Right), Some(Down)),
+                snake(Some(Right), Some(Left)),
+                snake(Some(Down), Some(Left)),
+                E,
+                E,
+            ],
+            [snake(Some(Up), None), E, snake(Some(Down), Some(Up)), E, E],
+            [
+                E,
+                snake(Some(Down), Some(Right)),
+                snake(Some(Left), Some(Up)),
+                E,
+                E,
+            ],
+            [E, snake(None, Some(Up)), E, E, E],
+            [E, E, E, E, E],
+        ]
+    };
+
+    #[test]
+    fn free_space_ahead() {
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = MockSeeder(0).get_rng();
+        let board = Board::from(POCKET_BOARD);
+        let game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.validate(), Ok(()));
+        assert_eq!(game_state.free_space_ahead(&Direction::Right), 0);
+        assert_eq!(game_state.free_space_ahead(&Direction::Down), 16);
+        assert_eq!(game_state.free_space_ahead(&Direction::Up), 0);
+    }
+
+    #[test]
+    fn distance_to_nearest_food() {
+        use dto::Cell::{Empty as E, Foods as F};
+        let head = snake(None, None);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = MockSeeder(0).get_rng();
+        let board = Board::from([[head, E, F, E, E]]);
+        let game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.distance_to_nearest_food(), Some(2));
+
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = MockSeeder(0).get_rng();
+        let board = Board::from([[head, E, E, E, F]]);
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.distance_to_nearest_food(), Some(1));
+        game_state.state.topology = Topology::Bounded;
+        assert_eq!(game_state.distance_to_nearest_food(), Some(4));
+    }
+
+    #[test]
+    fn nearest_food_tie_break() {
+        use dto::Cell::{Empty as E, Foods as F};
+        let head = snake(None, None);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = MockSeeder(0).get_rng();
+        let board = Board::from([
+            [E, E, F, E, E],
+            [E, E, E, E, E],
+            [F, E, head, E, E],
+            [E, E, E, E, E],
+            [E, E, F, E, E],
+        ]);
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.distance_to_nearest_food(), Some(2));
+        assert_eq!(game_state.nearest_food(), Some((0, 2)));
+        game_state.settings.tie_break = TieBreak::ColumnMajor;
+        assert_eq!(game_state.nearest_food(), Some((2, 0)));
+
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let rng = MockSeeder(0).get_rng();
+        let board = Board::from([
+            [E, E, E, E, E],
+            [E, E, E, E, E],
+            [F, E, head, E, E],
+            [E, E, E, E, E],
+            [E, E, F, E, E],
+        ]);
+        let mut game_state = GameState::from_board(board, &mut controller, &mut view, rng);
+        assert_eq!(game_state.nearest_food(), Some((2, 0)));
+        game_state.settings.tie_break = TieBreak::Clockwise;
+        assert_eq!(game_state.nearest_food(), Some((4, 2)));
+    }
+
+    #[test]
+    fn validate() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.validate(), Ok(()));
+        *game_state.state.board.at_mut(&Position(2, 1)) = Cell::Empty(0);
+        assert_eq!(game_state.validate(), Err(InvalidState::Empty));
+    }
+
+    #[test]
+    fn matches_ascii() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let game_state = harness.build();
+        game_state.assert_matches_ascii(
+            "
+            *..
+            .h.
+            ...
+            ",
+        );
+        assert!(!game_state.matches_ascii("...\n.h.\n..*"));
+        assert!(!game_state.matches_ascii("*..\n.h."));
+    }
+
+    #[test]
+    fn matches_ascii_round_trip() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(1)
+            .settings(|settings| settings.obstacles = vec![(2, 0)]);
+        let game_state = harness.build();
+        let ascii = game_state.render_to_string(&ASCII_THEME);
+        assert_eq!(ascii, "...\n*#.\n%..");
+        game_state.assert_matches_ascii(&ascii);
+        let cells = Vec::from_iter(ascii.lines().map(dto::cells_from_str));
+        assert_eq!(cells[2][0], dto::Cell::Obstacle);
+    }
+
+    #[test]
+    #[should_panic(expected = "*.. | *..\n.h. | .#.\n.*. | ...")]
+    fn assert_matches_ascii_mismatch() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let game_state = harness.build();
+        game_state.assert_matches_ascii("*..\n.h.\n.*.");
+    }
+
+    #[test]
+    fn pretty_debug() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let game_state = harness.build();
+        let pretty = game_state.pretty_debug();
+        let lines = Vec::from_iter(pretty.lines());
+        assert_eq!(lines[0], "  012");
+        assert_eq!(lines[2], "1 .@.");
+        assert_eq!(lines[4], "score 0, status Ongoing, length 1");
+    }
+
+    #[test]
+    fn render_to_string() {
+        let mut harness = GameHarness::<3, 3>::new().n_foods(1);
+        let game_state = harness.build();
+        let ascii = CellTheme {
+            empty: '.',
+            food: '*',
+            snake: '#',
+            obstacle: 'X',
+        };
+        let letters = CellTheme {
+            empty: 'e',
+            food: 'f',
+            snake: 's',
+            obstacle: 'o',
+        };
+        assert_eq!(game_state.render_to_string(&ascii), "*..\n.#.\n...");
+        assert_eq!(game_state.render_to_string(&letters), "fee\nese\neee");
+        assert_eq!(game_state.to_string(), "▒░░\n░█░\n░░░");
+    }
+
+    #[test]
+    fn iterate_turn_zero_foods() {
+        let mut harness = GameHarness::<3, 3>::new();
+        let mut game_state = harness.build();
+        for _ in 0..32 {
+            assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        }
+        assert_eq!(game_state.feed(), Ok(()));
+        assert_eq!(game_state.feed(), Ok(()));
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: false }
+        );
+    }
+
     const BOARD: [[Cell; 3]; 2] = [
         [
             Cell::Snake(Path {
@@ -359,7 +2129,7 @@ mod tests {
         let mut controller = MockController(Direction::Right);
         let mut view = MockView::default();
         let mut game_state = setup_loosable_board(&mut controller, &mut view);
-        let next_head = game_state.state.get_next_head(&Direction::Right);
+        let next_head = game_state.state.get_next_head(&Direction::Right).unwrap();
         let entry = Some(Direction::Left);
         game_state.insert_snake_head(next_head, entry);
         game_state.assert_is_snake_with_path(
@@ -408,17 +2178,89 @@ impl<const N_ROWS: usize, const N_COLS: usize> Options<N_ROWS, N_COLS> {
         controller: &'a mut dyn Controller,
         view: &'a mut dyn View,
     ) -> GameState<'a, N_ROWS, N_COLS> {
-        let state = State::new(board, self.seeder.get_rng());
-        GameState {
-            state,
-            controller,
-            view,
+        let state = State::new(board.clone(), ChaCha8Rng::seed_from_u64(0));
+        let mut game_state = GameState::new(state, controller, view);
+        self.init_game_state(&mut game_state, board);
+        game_state
+    }
+
+    fn init_game_state(
+        &self,
+        game_state: &mut GameState<N_ROWS, N_COLS>,
+        board: Board<N_ROWS, N_COLS>,
+    ) {
+        let seed = self.seeder.get_seed();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let heading = self.settings.seeded_heading.then(|| {
+            // A separate stream keeps the foods the same with or without a seeded heading
+            let mut heading_rng = ChaCha8Rng::seed_from_u64(seed);
+            heading_rng.set_stream(1);
+            heading_rng.gen::<Direction>()
+        });
+        let board = self.place_obstacles(board);
+        let board = self.place_first_food(board);
+        let board = if self.seeds_before_empty() {
+            self.seed_foods(board, &mut rng)
+        } else {
+            board
+        };
+        game_state.state = State::new(board, rng);
+        game_state.state.topology = self.settings.topology;
+        game_state.settings = self.settings.clone();
+        game_state.n_foods = self.settings.n_foods;
+        game_state.heading = heading;
+        game_state.score = 0;
+        game_state.seed = Some(seed);
+        game_state.directions.clear();
+        game_state.status = game_state.state.check_is_won_status();
+        game_state.pending_growth = 0;
+        game_state.spawn_countdown = self.settings.food_spawn_delay;
+        game_state.n_sequenced_foods = 0;
+        game_state.food_sequence_error = None;
+    }
+
+    fn place_first_food(&self, board: Board<N_ROWS, N_COLS>) -> Board<N_ROWS, N_COLS> {
+        match self.first_food_position() {
+            Some((i, j)) => {
+                let mut cells = board.to_dto();
+                cells[i][j] = dto::Cell::Foods;
+                Board::from(cells)
+            }
+            None => board,
+        }
+    }
+
+    fn place_obstacles(&self, board: Board<N_ROWS, N_COLS>) -> Board<N_ROWS, N_COLS> {
+        if self.settings.obstacles.is_empty() {
+            board
+        } else {
+            let mut cells = board.to_dto();
+            for &(i, j) in &self.settings.obstacles {
+                cells[i][j] = dto::Cell::Obstacle;
+            }
+            Board::from(cells)
+        }
+    }
+
+    fn seed_foods(
+        &self,
+        board: Board<N_ROWS, N_COLS>,
+        rng: &mut ChaCha8Rng,
+    ) -> Board<N_ROWS, N_COLS> {
+        let mut cells = board.to_dto();
+        let mut empty = board.get_empty();
+        for _ in 0..self.n_random_foods() {
+            let Position(i, j) = empty.remove(rng.gen_range(0..empty.len()));
+            cells[i][j] = dto::Cell::Foods;
         }
+        Board::from(cells)
     }
 
     fn add_foods(&self, game_state: &mut GameState<N_ROWS, N_COLS>) {
-        for _ in 0..self.n_foods {
-            game_state.insert_food().expect("room for foods");
+        if !self.seeds_before_empty() {
+            for _ in 0..self.n_random_foods() {
+                game_state.place_food().expect("room for foods");
+            }
         }
     }
 }
@@ -427,6 +2269,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> Options<N_ROWS, N_COLS> {
 mod options_tests {
     use super::*;
     use crate::controller::mock_controller::MockController;
+    use crate::game_state::FoodSeeding;
     use crate::view::MockView;
 
     const EXPECTED_BOARD: [[Cell; 3]; 3] = [
@@ -452,4 +2295,210 @@ mod options_tests {
         let board = Board::new(EXPECTED_BOARD);
         assert_eq!(game_state.state.board, board);
     }
+
+    #[test]
+    fn build_with_food_seeding_after_empty() {
+        let mut options = Options::<5, 5>::with_seed(2, 7);
+        options.settings.food_seeding = FoodSeeding::AfterEmpty;
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.state.foods, [Position(0, 4), Position(3, 2)]);
+    }
+
+    #[test]
+    fn build_with_food_seeding_before_empty() {
+        let mut options = Options::<5, 5>::with_seed(2, 7);
+        options.settings.food_seeding = FoodSeeding::BeforeEmpty;
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.state.foods, [Position(0, 4), Position(3, 3)]);
+        assert_eq!(view.0.len(), 25);
+        let foods = Vec::from_iter(
+            view.0
+                .iter()
+                .filter(|(_, cell)| *cell == dto::Cell::Foods)
+                .map(|(position, _)| *position),
+        );
+        assert_eq!(foods, [(0, 4), (3, 3)]);
+    }
+
+    #[test]
+    fn build_with_obstacles() {
+        let obstacles = [(0, 0), (2, 1)];
+        let options = Options::<3, 3>::with_seed(1, 0).with_obstacles(&obstacles);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        game_state.set_n_foods(6);
+        assert_eq!(game_state.state.empty, []);
+        assert_eq!(game_state.state.foods.len(), 6);
+        for position in obstacles {
+            assert_eq!(game_state.state.board.at(&position.into()), Cell::Obstacle);
+        }
+        assert_eq!(game_state.validate(), Ok(()));
+    }
+
+    #[test]
+    fn iterate_turn_obstacle() {
+        let options = Options::<3, 3>::with_seed(0, 0).with_obstacles(&[(1, 2)]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: false }
+        );
+    }
+
+    struct FixedSeeder;
+
+    impl crate::seeder::Seeder for FixedSeeder {
+        fn get_seed(&self) -> u64 {
+            0
+        }
+    }
+
+    #[test]
+    fn build_with_seeded_heading() {
+        let options = Options::<3, 3>::with_seed(1, 0).with_seeded_heading();
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.heading(), Some(Direction::Down));
+        let options = Options::<3, 3>::with_seed(1, 0);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.heading(), None);
+    }
+
+    #[test]
+    fn build_with_seeded_heading_first_move() {
+        let options = Options::<3, 3>::with_seed(1, 0).with_seeded_heading();
+        let mut controller = MockController(Direction::Up);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.forbidden_direction(), Some(Direction::Up));
+        game_state.iterate_turn();
+        assert_eq!(game_state.directions(), [Direction::Down]);
+        assert_eq!(game_state.state.snake.front(), Some(&Position(2, 1)));
+        assert_eq!(game_state.forbidden_direction(), None);
+        let options = Options::<3, 3>::with_seed(1, 0);
+        let mut controller = MockController(Direction::Up);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.forbidden_direction(), None);
+        game_state.iterate_turn();
+        assert_eq!(game_state.directions(), [Direction::Up]);
+        assert_eq!(game_state.state.snake.front(), Some(&Position(0, 1)));
+    }
+
+    #[test]
+    fn build_with_seeded_heading_same_foods() {
+        for food_seeding in [FoodSeeding::AfterEmpty, FoodSeeding::BeforeEmpty] {
+            let mut options = Options::<5, 5>::with_seed(3, 4);
+            options.settings.food_seeding = food_seeding;
+            let mut controller = MockController(Direction::Right);
+            let mut view = MockView::default();
+            let mut game_state = options.build(&mut controller, &mut view).unwrap();
+            let options = options.with_seeded_heading();
+            let mut controller_seeded = MockController(Direction::Right);
+            let mut view_seeded = MockView::default();
+            let mut game_state_seeded = options
+                .build(&mut controller_seeded, &mut view_seeded)
+                .unwrap();
+            for _ in 0..8 {
+                assert_eq!(game_state.state.foods, game_state_seeded.state.foods);
+                game_state.iterate_turn();
+                game_state_seeded.iterate_turn();
+            }
+        }
+    }
+
+    #[test]
+    fn build_with_first_food() {
+        let options = Options::<3, 3>::with_seed(2, 0).with_first_food(Direction::Right);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.state.board.at(&Position(1, 2)), Cell::Foods(0));
+        assert_eq!(game_state.state.foods.len(), 2);
+        assert!(view.0.contains(&((1, 2), dto::Cell::Foods)));
+    }
+
+    #[test]
+    fn run_random() {
+        let options = Options::<5, 5>::with_seed(3, 0);
+        let result = options.run_random(1, 8).unwrap();
+        assert!(result.n_turns > 0 && result.n_turns <= 8);
+        assert_eq!(options.run_random(1, 8), Ok(result));
+    }
+
+    #[test]
+    fn build_with_tie_break() {
+        let options = Options::<3, 3>::with_seed(1, 0).with_tie_break(TieBreak::Clockwise);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.settings.tie_break, TieBreak::Clockwise);
+    }
+
+    #[test]
+    fn build_with_food_sequence() {
+        let options =
+            Options::<1, 4>::with_seed(1, 0).with_food_sequence(&[(0, 3), (0, 0), (0, 1)]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.state.foods, [Position(0, 3)]);
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.state.foods, [Position(0, 0)]);
+        assert_eq!(game_state.iterate_turn(), dto::Status::Ongoing);
+        assert_eq!(game_state.state.foods, [Position(0, 1)]);
+        assert_eq!(
+            game_state.iterate_turn(),
+            dto::Status::Over { is_won: true }
+        );
+        assert_eq!(game_state.score(), 3);
+    }
+
+    #[test]
+    fn build_with_food_sequence_exhausted() {
+        let options = Options::<1, 4>::with_seed(1, 0).with_food_sequence(&[(0, 3)]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(
+            game_state.try_iterate_turn(),
+            Err(FoodSequenceError::Exhausted)
+        );
+        assert_eq!(game_state.score(), 1);
+        assert!(game_state.state.foods.is_empty());
+        assert_eq!(game_state.status(), dto::Status::Ongoing);
+    }
+
+    #[test]
+    fn build_with_food_sequence_occupied() {
+        let options = Options::<1, 4>::with_seed(1, 0).with_food_sequence(&[(0, 3), (0, 2)]);
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(
+            game_state.try_iterate_turn(),
+            Err(FoodSequenceError::Occupied((0, 2)))
+        );
+        assert!(game_state.state.foods.is_empty());
+    }
+
+    #[test]
+    fn build_with_seeder() {
+        let options = Options::<3, 3>::with_seeder(1, Box::new(FixedSeeder));
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        let board = Board::new(EXPECTED_BOARD);
+        assert_eq!(game_state.state.board, board);
+    }
 }
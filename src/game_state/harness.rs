@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use crate::controller::{
+    mock_controller::{MockController, ScriptedController},
+    Controller,
+};
+use crate::data_transfer_objects::Direction;
+use crate::seeder::MockSeeder;
+use crate::view::MockView;
+
+use super::{GameState, Options, Settings};
+
+/// Deterministic test setup that owns the controller and view a built `GameState` borrows
+#[derive(Debug)]
+pub struct GameHarness<const N_ROWS: usize, const N_COLS: usize> {
+    seed: u64,
+    settings: Settings,
+    controller: Box<dyn Controller>,
+    pub view: MockView,
+}
+
+impl<const N_ROWS: usize, const N_COLS: usize> GameHarness<N_ROWS, N_COLS> {
+    /// Seed `0` with no foods and a controller that always moves right
+    pub fn new() -> Self {
+        GameHarness {
+            seed: 0,
+            settings: Settings {
+                n_foods: 0,
+                ..Settings::default()
+            },
+            controller: MockController::boxed(Direction::Right),
+            view: MockView::default(),
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn n_foods(mut self, n_foods: usize) -> Self {
+        self.settings.n_foods = n_foods;
+        self
+    }
+
+    /// Changes any other setting the game is built with
+    pub fn settings(mut self, f: impl FnOnce(&mut Settings)) -> Self {
+        f(&mut self.settings);
+        self
+    }
+
+    /// Always moves in `direction`
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.controller = MockController::boxed(direction);
+        self
+    }
+
+    /// Plays back `directions` in order, panicking once they run out
+    pub fn directions(mut self, directions: impl IntoIterator<Item = Direction>) -> Self {
+        let directions = VecDeque::from_iter(directions);
+        self.controller = Box::new(ScriptedController(directions));
+        self
+    }
+
+    /// This panics when the options are invalid
+    pub fn build(&mut self) -> GameState<'_, N_ROWS, N_COLS> {
+        let options = Options::<N_ROWS, N_COLS> {
+            seeder: Box::new(MockSeeder(self.seed)),
+            settings: self.settings.clone(),
+        };
+        options
+            .build(self.controller.as_mut(), &mut self.view)
+            .expect("valid harness options")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_with_settings() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .n_foods(1)
+            .settings(|settings| settings.obstacles = vec![(0, 0)]);
+        let game_state = harness.build();
+        assert_eq!(game_state.foods_on_board(), 1);
+        let cells = game_state.state.board.to_dto();
+        assert_eq!(cells[0][0], crate::data_transfer_objects::Cell::Obstacle);
+    }
+
+    #[test]
+    fn build() {
+        let mut harness = GameHarness::<3, 3>::new()
+            .seed(2)
+            .n_foods(2)
+            .directions([Direction::Up]);
+        let mut game_state = harness.build();
+        assert_eq!(game_state.seed(), Some(2));
+        assert_eq!(game_state.foods_on_board(), 2);
+        game_state.iterate_turn();
+        assert_eq!(game_state.directions(), [Direction::Up]);
+        drop(game_state);
+        assert!(!harness.view.0.is_empty());
+    }
+}
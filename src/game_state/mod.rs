@@ -1,6 +1,17 @@
+#[allow(clippy::module_inception)]
 mod game_state;
+#[cfg(test)]
+mod harness;
 mod options;
+mod simulation;
 mod state;
+mod trace;
 
-pub use game_state::GameState;
-pub use options::Options;
+pub use game_state::{FoodSequenceError, GameState, NoRoom};
+pub use options::{Builder, FoodSeeding, InvalidOptions, Options, Settings, TieBreak, Topology};
+pub use simulation::{RunIter, SimulationResult};
+pub use state::{board::NoSnakeHead, state::InvalidState};
+pub use trace::{InvalidTrace, Trace};
+
+#[cfg(feature = "test-util")]
+pub use state::{board::Board, state::State, Cell, Position};
@@ -1,31 +1,246 @@
-use crate::controller::Controller;
+use crate::controller::{random_controller::RandomController, Controller};
+use crate::data_transfer_objects as dto;
 use crate::seeder::*;
-use crate::view::View;
+use crate::view::{NullView, View};
 
+use super::simulation::SimulationResult;
+use super::state::{board::Board, Position};
 use super::GameState;
 
-#[derive(Debug)]
-pub struct InvalidOptions;
+#[derive(Debug, PartialEq)]
+pub enum InvalidOptions {
+    /// The board can't fit the snake, foods, and obstacles, or without foods the snake already
+    /// fills every open cell and the game would start over
+    NotEnoughRoom,
+    /// An obstacle lies outside the board
+    ObstacleOutOfBounds,
+    /// An obstacle covers the snake's starting cell
+    ObstacleOnSnake,
+    /// `growth_per_food` is `0`, while any other growth wins once the board fills
+    ZeroGrowth,
+    /// The first food can't go next to the head, or there are no foods to place
+    FirstFoodBlocked,
+    /// The food sequence leaves the board, can't cover the starting foods, or starts a food on
+    /// an occupied cell
+    InvalidFoodSequence,
+}
 
-pub struct Options<const N_ROWS: usize, const N_COLS: usize> {
+/// When foods are drawn relative to building the `empty` list, pinned so a seed keeps
+/// mapping to the same food positions
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FoodSeeding {
+    /// Foods are drawn from the built `empty` list, which each draw reorders
+    #[default]
+    AfterEmpty,
+    /// Foods are drawn from the row-major empty cells before the `empty` list is built
+    BeforeEmpty,
+}
+
+/// Which board edges the snake wraps across, running into any other edge
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    #[default]
+    Torus,
+    Bounded,
+    /// Wraps rows across the left and right edges, or else columns across the top and bottom
+    Cylinder {
+        wrap_rows: bool,
+    },
+}
+
+impl Topology {
+    pub fn wraps_horizontally(&self) -> bool {
+        matches!(
+            self,
+            Topology::Torus | Topology::Cylinder { wrap_rows: true }
+        )
+    }
+
+    pub fn wraps_vertically(&self) -> bool {
+        matches!(
+            self,
+            Topology::Torus | Topology::Cylinder { wrap_rows: false }
+        )
+    }
+}
+
+/// Which of several equally near foods `nearest_food` picks
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TieBreak {
+    #[default]
+    RowMajor,
+    ColumnMajor,
+    /// The first food sweeping clockwise around the head from straight up
+    Clockwise,
+}
+
+/// The settings a game is built with, kept by `GameState` so a reset or trace rebuilds the
+/// same game
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// With `0` foods the snake never grows, so the game can't be won and only ends when the
+    /// snake runs into itself
     pub n_foods: usize,
+    pub food_seeding: FoodSeeding,
+    pub obstacles: Vec<dto::Position>,
+    /// Whether the snake starts facing a direction drawn from the seed instead of none, which
+    /// its first move can't reverse, leaving the foods unchanged
+    pub seeded_heading: bool,
+    /// How many cells the snake grows per food, over the turns after eating
+    pub growth_per_food: usize,
+    /// With more than `0`, eaten foods are replaced one every this many turns instead of at once
+    pub food_spawn_delay: usize,
+    pub topology: Topology,
+    /// Places the first food one cell from the head in this direction instead of at random
+    pub first_food: Option<dto::Direction>,
+    pub tie_break: TieBreak,
+    /// Places foods on these cells in order instead of drawing them from the rng, leaving the
+    /// food out once they run out or when the next one is taken
+    pub food_sequence: Option<Vec<dto::Position>>,
+}
+
+/// One food and no extra settings
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            n_foods: 1,
+            food_seeding: FoodSeeding::default(),
+            obstacles: Vec::new(),
+            seeded_heading: false,
+            growth_per_food: 1,
+            food_spawn_delay: 0,
+            topology: Topology::default(),
+            first_food: None,
+            tie_break: TieBreak::default(),
+            food_sequence: None,
+        }
+    }
+}
+
+pub struct Options<const N_ROWS: usize, const N_COLS: usize> {
     pub seeder: Box<dyn Seeder>,
+    pub settings: Settings,
 }
 
 impl<const N_ROWS: usize, const N_COLS: usize> Options<N_ROWS, N_COLS> {
     pub fn new(n_foods: usize) -> Self {
-        Options {
-            n_foods,
-            seeder: Box::new(SecondsSeeder::SECONDS_SEEDER),
-        }
+        Options::with_seeder(n_foods, Box::new(SecondsSeeder::SECONDS_SEEDER))
     }
 
     pub fn with_seed(n_foods: usize, seed: u64) -> Self {
+        Options::with_seeder(n_foods, Box::new(MockSeeder(seed)))
+    }
+
+    pub fn with_seeder(n_foods: usize, seeder: Box<dyn Seeder>) -> Self {
         Options {
-            n_foods,
-            seeder: Box::new(MockSeeder(seed)),
+            seeder,
+            settings: Settings {
+                n_foods,
+                ..Settings::default()
+            },
         }
     }
+
+    /// Lays obstacles on these cells when the game is built
+    pub fn with_obstacles(mut self, positions: &[dto::Position]) -> Self {
+        self.settings.obstacles.extend_from_slice(positions);
+        self
+    }
+
+    /// Starts the snake facing a direction drawn from the seed
+    pub fn with_seeded_heading(mut self) -> Self {
+        self.settings.seeded_heading = true;
+        self
+    }
+
+    pub fn with_growth_per_food(mut self, growth_per_food: usize) -> Self {
+        self.settings.growth_per_food = growth_per_food;
+        self
+    }
+
+    pub fn with_food_spawn_delay(mut self, food_spawn_delay: usize) -> Self {
+        self.settings.food_spawn_delay = food_spawn_delay;
+        self
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.settings.topology = topology;
+        self
+    }
+
+    /// Scales `n_foods` with the board, one per hundred cells and at least one
+    pub fn auto_foods(mut self) -> Self {
+        self.settings.n_foods = (self.area() / 100).max(1);
+        self
+    }
+
+    pub fn with_first_food(mut self, direction: dto::Direction) -> Self {
+        self.settings.first_food = Some(direction);
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.settings.tie_break = tie_break;
+        self
+    }
+
+    /// Places foods from `positions` in order so the game doesn't depend on the rng
+    pub fn with_food_sequence(mut self, positions: &[dto::Position]) -> Self {
+        self.settings.food_sequence = Some(positions.to_vec());
+        self
+    }
+}
+
+/// One food with a seed from the clock
+impl<const N_ROWS: usize, const N_COLS: usize> Default for Options<N_ROWS, N_COLS> {
+    fn default() -> Self {
+        Options::new(1)
+    }
+}
+
+/// `Options` with a runtime shape for callers that don't know the shape at compile time
+pub struct Builder {
+    pub n_rows: usize,
+    pub n_cols: usize,
+    pub n_foods: usize,
+    pub seeder: Box<dyn Seeder>,
+}
+
+impl Builder {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        Builder {
+            n_rows,
+            n_cols,
+            n_foods: 1,
+            seeder: Box::new(SecondsSeeder::SECONDS_SEEDER),
+        }
+    }
+
+    pub fn n_foods(mut self, n_foods: usize) -> Self {
+        self.n_foods = n_foods;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seeder = Box::new(MockSeeder(seed));
+        self
+    }
+}
+
+/// This panics when the builder's shape isn't `(N_ROWS, N_COLS)`
+impl<const N_ROWS: usize, const N_COLS: usize> From<Builder> for Options<N_ROWS, N_COLS> {
+    fn from(builder: Builder) -> Self {
+        assert_eq!(
+            (builder.n_rows, builder.n_cols),
+            (N_ROWS, N_COLS),
+            "builder shape"
+        );
+        Options::with_seeder(builder.n_foods, builder.seeder)
+    }
 }
 
 impl<const N_ROWS: usize, const N_COLS: usize> Options<N_ROWS, N_COLS> {
@@ -34,15 +249,101 @@ impl<const N_ROWS: usize, const N_COLS: usize> Options<N_ROWS, N_COLS> {
         controller: &'a mut dyn Controller,
         view: &'a mut dyn View,
     ) -> Result<GameState<'a, N_ROWS, N_COLS>, InvalidOptions> {
-        if self.is_valid() {
-            Ok(GameState::from_options(self, controller, view))
+        self.validate()?;
+        Ok(GameState::from_options(self, controller, view))
+    }
+
+    /// Plays up to `max_turns` headless turns with a `RandomController` seeded by `seed`, since
+    /// a built game only borrows its controller
+    pub fn run_random(
+        &self,
+        seed: u64,
+        max_turns: usize,
+    ) -> Result<SimulationResult, InvalidOptions> {
+        let mut controller = RandomController::new(&mut MockSeeder(seed));
+        let mut view = NullView;
+        let mut game_state = self.build(&mut controller, &mut view)?;
+        let n_turns = game_state.run_iter(max_turns).count();
+        Ok(SimulationResult {
+            status: game_state.status(),
+            score: game_state.score(),
+            n_turns,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidOptions> {
+        let head: dto::Position = Board::<N_ROWS, N_COLS>::center().into();
+        if self.area() < self.n_non_empty()
+            || (self.settings.n_foods == 0 && self.area() == self.n_non_empty())
+        {
+            Err(InvalidOptions::NotEnoughRoom)
+        } else if self
+            .settings
+            .obstacles
+            .iter()
+            .any(|&(i, j)| i >= N_ROWS || j >= N_COLS)
+        {
+            Err(InvalidOptions::ObstacleOutOfBounds)
+        } else if self.settings.obstacles.contains(&head) {
+            Err(InvalidOptions::ObstacleOnSnake)
+        } else if self.settings.growth_per_food == 0 {
+            Err(InvalidOptions::ZeroGrowth)
+        } else if self.settings.first_food.is_some()
+            && (self.settings.n_foods == 0
+                || self.first_food_position().is_none_or(|position| {
+                    position == head || self.settings.obstacles.contains(&position)
+                }))
+        {
+            Err(InvalidOptions::FirstFoodBlocked)
+        } else if self
+            .settings
+            .food_sequence
+            .as_ref()
+            .is_some_and(|positions| {
+                let starting = positions.get(..self.n_random_foods()).unwrap_or(positions);
+                positions.len() < self.n_random_foods()
+                    || positions.iter().any(|&(i, j)| i >= N_ROWS || j >= N_COLS)
+                    || starting.iter().enumerate().any(|(k, position)| {
+                        *position == head
+                            || self.settings.obstacles.contains(position)
+                            || self.first_food_position() == Some(*position)
+                            || starting[..k].contains(position)
+                    })
+            })
+        {
+            Err(InvalidOptions::InvalidFoodSequence)
         } else {
-            Err(InvalidOptions)
+            Ok(())
         }
     }
 
-    fn is_valid(&self) -> bool {
-        self.area() >= self.n_non_empty()
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Where `first_food` places the first food, unless it's off the board
+    pub(super) fn first_food_position(&self) -> Option<dto::Position> {
+        let head = Board::<N_ROWS, N_COLS>::center();
+        let direction = self.settings.first_food?;
+        Board::<N_ROWS, N_COLS>::default()
+            .move_in(&head, &direction, self.settings.topology)
+            .map(Position::into)
+    }
+
+    /// Whether foods are drawn before building the `empty` list, which a food sequence skips
+    pub(super) fn seeds_before_empty(&self) -> bool {
+        self.settings.food_seeding == FoodSeeding::BeforeEmpty
+            && self.settings.food_sequence.is_none()
+    }
+
+    /// How many foods are drawn from the rng
+    pub(super) fn n_random_foods(&self) -> usize {
+        self.settings.n_foods - usize::from(self.settings.first_food.is_some())
+    }
+
+    /// `(N_ROWS, N_COLS)` for code that only holds the options
+    pub const fn shape(&self) -> (usize, usize) {
+        (N_ROWS, N_COLS)
     }
 
     fn area(&self) -> usize {
@@ -51,7 +352,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> Options<N_ROWS, N_COLS> {
 
     fn n_non_empty(&self) -> usize {
         let n_snake = 1;
-        self.n_foods + n_snake
+        self.settings.n_foods + n_snake + self.settings.obstacles.len()
     }
 }
 
@@ -63,13 +364,113 @@ mod options_tests {
 
     use super::*;
 
+    #[test]
+    fn build_default() {
+        let options = Options::<20, 20>::default();
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        let game_state = options.build(&mut controller, &mut view).unwrap();
+        assert_eq!(game_state.snake_len(), 1);
+    }
+
+    #[test]
+    fn auto_foods() {
+        let small = Options::<10, 10>::with_seed(0, 0).auto_foods();
+        let large = Options::<50, 50>::with_seed(0, 0).auto_foods();
+        assert_eq!(small.settings.n_foods, 1);
+        assert!(large.settings.n_foods > small.settings.n_foods);
+    }
+
     #[test]
     fn build_with_invalid() {
         let options = Options::<3, 3>::with_seed(9, 0);
         let mut controller = MockController(Direction::Right);
         let mut view = MockView::default();
         let game_state = options.build(&mut controller, &mut view).unwrap_err();
-        assert!(matches!(game_state, InvalidOptions));
+        assert!(matches!(game_state, InvalidOptions::NotEnoughRoom));
+    }
+
+    #[test]
+    fn validate_zero_growth() {
+        let options = Options::<1, 2>::with_seed(1, 0).with_growth_per_food(0);
+        assert_eq!(options.validate(), Err(InvalidOptions::ZeroGrowth));
+        let options = Options::<1, 2>::with_seed(1, 0).with_growth_per_food(2);
+        assert_eq!(options.validate(), Ok(()));
+        let options = Options::<3, 3>::with_seed(1, 0).with_growth_per_food(3);
+        assert_eq!(options.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_first_food_blocked() {
+        let options = Options::<3, 3>::with_seed(1, 0)
+            .with_topology(Topology::Bounded)
+            .with_first_food(Direction::Up);
+        assert_eq!(options.validate(), Ok(()));
+        let options = Options::<3, 3>::with_seed(0, 0).with_first_food(Direction::Up);
+        assert_eq!(options.validate(), Err(InvalidOptions::FirstFoodBlocked));
+        let options = Options::<3, 1>::with_seed(1, 0)
+            .with_topology(Topology::Bounded)
+            .with_first_food(Direction::Right);
+        assert_eq!(options.validate(), Err(InvalidOptions::FirstFoodBlocked));
+        let options = Options::<3, 3>::with_seed(1, 0)
+            .with_obstacles(&[(1, 2)])
+            .with_first_food(Direction::Right);
+        assert_eq!(options.validate(), Err(InvalidOptions::FirstFoodBlocked));
+    }
+
+    #[test]
+    fn validate_invalid_food_sequence() {
+        let options = Options::<3, 3>::with_seed(2, 0).with_food_sequence(&[(0, 0)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::InvalidFoodSequence));
+        let options = Options::<3, 3>::with_seed(1, 0).with_food_sequence(&[(3, 0)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::InvalidFoodSequence));
+        let options = Options::<3, 3>::with_seed(1, 0).with_food_sequence(&[(1, 1)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::InvalidFoodSequence));
+        let options = Options::<3, 3>::with_seed(1, 0)
+            .with_obstacles(&[(0, 0)])
+            .with_food_sequence(&[(0, 0)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::InvalidFoodSequence));
+        let options = Options::<3, 3>::with_seed(2, 0)
+            .with_first_food(Direction::Right)
+            .with_food_sequence(&[(1, 2)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::InvalidFoodSequence));
+        let options = Options::<3, 3>::with_seed(2, 0).with_food_sequence(&[(0, 0), (0, 0)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::InvalidFoodSequence));
+        let options = Options::<3, 3>::with_seed(1, 0).with_food_sequence(&[(0, 0), (1, 1)]);
+        assert_eq!(options.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_obstacle_out_of_bounds() {
+        let options = Options::<3, 3>::with_seed(1, 0).with_obstacles(&[(0, 3)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::ObstacleOutOfBounds));
+    }
+
+    #[test]
+    fn validate_obstacle_on_snake() {
+        let options = Options::<3, 3>::with_seed(1, 0).with_obstacles(&[(1, 1)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::ObstacleOnSnake));
+    }
+
+    #[test]
+    fn n_non_empty_with_obstacles() {
+        let options = Options::<3, 3>::with_seed(1, 0).with_obstacles(&[(0, 0), (0, 1)]);
+        assert_eq!(options.n_non_empty(), 4);
+    }
+
+    #[test]
+    fn from_builder() {
+        let options: Options<3, 4> = Builder::new(3, 4).n_foods(2).seed(0).into();
+        let mut controller = MockController(Direction::Right);
+        let mut view = MockView::default();
+        assert_eq!(options.settings.n_foods, 2);
+        assert!(options.build(&mut controller, &mut view).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "builder shape")]
+    fn from_builder_mismatch() {
+        let _: Options<3, 3> = Builder::new(3, 4).into();
     }
 
     #[test]
@@ -84,6 +485,21 @@ mod options_tests {
         assert!(!options.is_valid());
     }
 
+    #[test]
+    fn validate_zero_foods_no_room() {
+        let options = Options::<1, 1>::with_seed(0, 0);
+        assert_eq!(options.validate(), Err(InvalidOptions::NotEnoughRoom));
+        let options = Options::<1, 3>::with_seed(0, 0).with_obstacles(&[(0, 0), (0, 2)]);
+        assert_eq!(options.validate(), Err(InvalidOptions::NotEnoughRoom));
+        let options = Options::<1, 2>::with_seed(0, 0);
+        assert_eq!(options.validate(), Ok(()));
+    }
+
+    #[test]
+    fn shape() {
+        assert_eq!(Options::<4, 7>::new(1).shape(), (4, 7));
+    }
+
     #[test]
     fn area() {
         let options = Options::<3, 4>::with_seed(1, 0);
@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+
+use crate::controller::mock_controller::ScriptedController;
+use crate::data_transfer_objects as dto;
+use crate::view::NullView;
+
+use super::{
+    options::{InvalidOptions, Options},
+    state::Direction,
+    GameState,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SimulationResult {
+    pub status: dto::Status,
+    pub score: usize,
+    pub n_turns: usize,
+}
+
+impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS> {
+    /// Replays `directions` on a game seeded with `seed` until it's over or they run out
+    pub fn replay_from(
+        n_foods: usize,
+        seed: u64,
+        directions: &[Direction],
+    ) -> Result<SimulationResult, InvalidOptions> {
+        let mut controller = ScriptedController(VecDeque::from(directions.to_vec()));
+        let mut view = NullView;
+        let mut game_state = Options::<N_ROWS, N_COLS>::with_seed(n_foods, seed)
+            .build(&mut controller, &mut view)?;
+        let mut status = dto::Status::Ongoing;
+        let mut n_turns = 0;
+        while status == dto::Status::Ongoing && n_turns < directions.len() {
+            status = game_state.iterate_turn();
+            n_turns += 1;
+        }
+        Ok(SimulationResult {
+            status,
+            score: game_state.score(),
+            n_turns,
+        })
+    }
+
+    /// Lazily plays turns, yielding the status and frame after each until the game is over or
+    /// `max_ticks` turns have been played
+    pub fn run_iter(&mut self, max_ticks: usize) -> RunIter<'_, 'a, N_ROWS, N_COLS> {
+        RunIter {
+            game_state: self,
+            ticks_left: max_ticks,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RunIter<'g, 'a, const N_ROWS: usize, const N_COLS: usize> {
+    game_state: &'g mut GameState<'a, N_ROWS, N_COLS>,
+    ticks_left: usize,
+}
+
+impl<'g, 'a, const N_ROWS: usize, const N_COLS: usize> Iterator
+    for RunIter<'g, 'a, N_ROWS, N_COLS>
+{
+    type Item = (dto::Status, dto::Frame);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ticks_left == 0 || self.game_state.status() != dto::Status::Ongoing {
+            None
+        } else {
+            self.ticks_left -= 1;
+            let status = self.game_state.iterate_turn();
+            let (frame, _, _) = self.game_state.snapshot_dto();
+            Some((status, frame))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::view::MockView;
+
+    use super::*;
+
+    const DIRECTIONS: [Direction; 8] = [
+        Direction::Right,
+        Direction::Right,
+        Direction::Down,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+        Direction::Up,
+        Direction::Up,
+    ];
+
+    #[test]
+    fn replay_from() {
+        let mut controller = ScriptedController(VecDeque::from(DIRECTIONS));
+        let mut view = MockView::default();
+        let mut game_state = Options::<4, 4>::with_seed(4, 1)
+            .build(&mut controller, &mut view)
+            .unwrap();
+        let statuses = Vec::from_iter(DIRECTIONS.iter().map(|_| game_state.iterate_turn()));
+        let score = game_state.score();
+        let result = GameState::<4, 4>::replay_from(4, 1, &DIRECTIONS).unwrap();
+        assert_eq!(result.status, *statuses.last().unwrap());
+        assert_eq!(result.score, score);
+        assert_eq!(result.n_turns, DIRECTIONS.len());
+        assert!(score > 0);
+    }
+
+    #[test]
+    fn run_iter() {
+        let mut controller = ScriptedController(VecDeque::from([Direction::Right; 2]));
+        let mut view = MockView::default();
+        let mut game_state = Options::<1, 3>::with_seed(1, 0)
+            .build(&mut controller, &mut view)
+            .unwrap();
+        let frames = Vec::from_iter(game_state.run_iter(5));
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].0, dto::Status::Ongoing);
+        let (status, frame) = frames.last().unwrap();
+        assert_eq!(*status, dto::Status::Over { is_won: true });
+        assert!(frame[0]
+            .iter()
+            .all(|cell| matches!(cell, dto::Cell::Snake(_))));
+    }
+
+    #[test]
+    fn run_iter_max_ticks() {
+        let mut controller = ScriptedController(VecDeque::from(DIRECTIONS));
+        let mut view = MockView::default();
+        let mut game_state = Options::<4, 4>::with_seed(4, 1)
+            .build(&mut controller, &mut view)
+            .unwrap();
+        assert_eq!(game_state.run_iter(3).count(), 3);
+        assert_eq!(game_state.directions(), &DIRECTIONS[..3]);
+    }
+
+    #[test]
+    fn replay_from_invalid() {
+        let result = GameState::<1, 1>::replay_from(1, 0, &DIRECTIONS);
+        assert!(result.is_err());
+    }
+}
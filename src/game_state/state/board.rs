@@ -2,21 +2,26 @@ use std::collections::VecDeque;
 
 use crate::data_transfer_objects as _dto; // Limited usage in `from`
 
+use super::super::options::Topology;
 use super::value_objects::*;
 
 // TODO: snake assertions
 
+#[derive(Debug, PartialEq)]
+pub struct NoSnakeHead;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Board<const N_ROWS: usize, const N_COLS: usize>([[Cell; N_COLS]; N_ROWS]);
 
 impl<const N_ROWS: usize, const N_COLS: usize> Default for Board<N_ROWS, N_COLS> {
     fn default() -> Self {
+        let center = Board::<N_ROWS, N_COLS>::center();
         let mut empty_index = 0;
         let board = (0..N_ROWS)
             .map(|i| {
                 (0..N_COLS)
                     .map(|j| {
-                        if i == N_ROWS / 2 && j == N_COLS / 2 {
+                        if Position(i, j) == center {
                             Cell::Snake(Path {
                                 entry: None,
                                 exit: None,
@@ -43,6 +48,10 @@ impl<const N_ROWS: usize, const N_COLS: usize> Board<N_ROWS, N_COLS> {
         Board(board)
     }
 
+    pub fn to_dto(&self) -> [[_dto::Cell; N_COLS]; N_ROWS] {
+        self.0.map(|row| row.map(_dto::Cell::from))
+    }
+
     pub fn get_empty(&self) -> Vec<Position> {
         Vec::from_iter(self.0.iter().enumerate().flat_map(|(i, row)| {
             row.iter()
@@ -62,17 +71,69 @@ impl<const N_ROWS: usize, const N_COLS: usize> Board<N_ROWS, N_COLS> {
     }
 
     pub fn get_snake(&self) -> VecDeque<Position> {
-        let mut position = self.find_snake_head().expect("snake head");
+        self.try_get_snake().expect("snake head")
+    }
+
+    pub fn try_get_snake(&self) -> Result<VecDeque<Position>, NoSnakeHead> {
+        let mut position = self.find_snake_head().ok_or(NoSnakeHead)?;
         let mut snake = VecDeque::from([position]);
         while let Cell::Snake(Path {
             entry: Some(direction),
             exit: _,
         }) = self.at(&position)
         {
-            position = self.move_in(&position, &direction);
+            position = self
+                .move_in(&position, &direction, Topology::Torus)
+                .expect("torus move");
             snake.push_back(position);
         }
-        snake
+        Ok(snake)
+    }
+
+    /// Every `Position` with its `Cell` in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (Position, Cell)> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(j, &cell)| (Position(i, j), cell))
+        })
+    }
+
+    /// Swaps two cells along with any `Empty` or `Foods` indices they hold, so the owning
+    /// `State` must swap the matching entries in `empty` and `foods` to stay consistent
+    pub fn swap(&mut self, a: &Position, b: &Position) {
+        let cell = self.at(a);
+        *self.at_mut(a) = self.at(b);
+        *self.at_mut(b) = cell;
+    }
+
+    /// The min and max row and column of the snake, or `None` without a snake, which ignores
+    /// wrapping so a snake across an edge spans the whole axis
+    pub fn snake_bounds(&self) -> Option<(Position, Position)> {
+        self.iter()
+            .filter(|(_, cell)| matches!(cell, Cell::Snake(_)))
+            .map(|(position, _)| (position, position))
+            .reduce(|(min, max), (Position(i, j), _)| {
+                (
+                    Position(min.0.min(i), min.1.min(j)),
+                    Position(max.0.max(i), max.1.max(j)),
+                )
+            })
+    }
+
+    /// Where the snake starts, rounding down on even sides
+    pub const fn center() -> Position {
+        Position(N_ROWS / 2, N_COLS / 2)
+    }
+
+    /// The row-major index of `position` in a flattened board
+    pub fn to_index(position: &Position) -> usize {
+        position.0 * N_COLS + position.1
+    }
+
+    /// The position at a row-major `index`, the inverse of `to_index`
+    pub fn from_index(index: usize) -> Position {
+        Position(index / N_COLS, index % N_COLS)
     }
 
     pub fn at(&self, position: &Position) -> Cell {
@@ -102,19 +163,27 @@ impl<const N_ROWS: usize, const N_COLS: usize> Board<N_ROWS, N_COLS> {
         })
     }
 
-    pub fn move_in(&self, position: &Position, direction: &Direction) -> Position {
+    /// The position one step in `direction`, or `None` when that step crosses an edge
+    /// `topology` doesn't wrap
+    pub fn move_in(
+        &self,
+        position: &Position,
+        direction: &Direction,
+        topology: Topology,
+    ) -> Option<Position> {
         let velocity = direction.as_velocity();
-        let i = position
-            .0
-            .checked_add_signed(velocity.0)
-            .unwrap_or(N_ROWS - Velocity::DEFAULT_MAGNITUDE)
-            % N_ROWS;
-        let j = position
-            .1
-            .checked_add_signed(velocity.1)
-            .unwrap_or(N_COLS - Velocity::DEFAULT_MAGNITUDE)
-            % N_COLS;
-        Position(i, j)
+        let i = position.0.checked_add_signed(velocity.0);
+        let j = position.1.checked_add_signed(velocity.1);
+        let is_i_inside = i.is_some_and(|i| i < N_ROWS);
+        let is_j_inside = j.is_some_and(|j| j < N_COLS);
+        if !is_i_inside && !topology.wraps_vertically()
+            || !is_j_inside && !topology.wraps_horizontally()
+        {
+            return None;
+        }
+        let i = i.unwrap_or(N_ROWS - Velocity::DEFAULT_MAGNITUDE) % N_ROWS;
+        let j = j.unwrap_or(N_COLS - Velocity::DEFAULT_MAGNITUDE) % N_COLS;
+        Some(Position(i, j))
     }
 }
 
@@ -137,6 +206,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> From<[[_dto::Cell; N_COLS]; N_ROW
                     Cell::Foods(foods_index)
                 }
                 _dto::Cell::Snake(path) => Cell::Snake(path),
+                _dto::Cell::Obstacle => Cell::Obstacle,
             })
         });
         Board::new(board)
@@ -194,6 +264,88 @@ mod tests {
         assert_eq!(snake, EXPECTED_SNAKE);
     }
 
+    #[test]
+    fn try_get_snake_headless() {
+        let board = Board::new([[Cell::Empty(0), Cell::Foods(0)]]);
+        assert_eq!(board.try_get_snake(), Err(NoSnakeHead));
+    }
+
+    #[test]
+    fn iter() {
+        let board = Board::new(INPUT_BOARD);
+        let cells = Vec::from_iter(board.iter());
+        assert_eq!(cells.len(), 9);
+        assert_eq!(cells[1], (Position(0, 1), Cell::Foods(0)));
+        assert_eq!(cells[8], (Position(2, 2), Cell::Empty(4)));
+    }
+
+    #[test]
+    fn swap() {
+        let mut board = Board::new(INPUT_BOARD);
+        board.swap(&Position(0, 0), &Position(2, 2));
+        assert_eq!(board.at(&Position(0, 0)), Cell::Empty(4));
+        assert_eq!(board.at(&Position(2, 2)), Cell::Empty(0));
+    }
+
+    #[test]
+    fn snake_bounds() {
+        let board = Board::new(INPUT_BOARD);
+        let bounds = board.snake_bounds();
+        assert_eq!(bounds, Some((Position(1, 0), Position(2, 1))));
+        let board = Board::new([[Cell::Empty(0), Cell::Obstacle]]);
+        assert_eq!(board.snake_bounds(), None);
+    }
+
+    #[test]
+    fn move_in_topology() {
+        let board = Board::new(INPUT_BOARD);
+        let corner = Position(0, 0);
+        let rows = Topology::Cylinder { wrap_rows: true };
+        let cols = Topology::Cylinder { wrap_rows: false };
+        assert_eq!(
+            board.move_in(&corner, &Direction::Left, Topology::Torus),
+            Some(Position(0, 2))
+        );
+        assert_eq!(
+            board.move_in(&corner, &Direction::Left, Topology::Bounded),
+            None
+        );
+        assert_eq!(
+            board.move_in(&corner, &Direction::Up, Topology::Bounded),
+            None
+        );
+        assert_eq!(
+            board.move_in(&corner, &Direction::Right, Topology::Bounded),
+            Some(Position(0, 1))
+        );
+        assert_eq!(
+            board.move_in(&corner, &Direction::Left, rows),
+            Some(Position(0, 2))
+        );
+        assert_eq!(board.move_in(&corner, &Direction::Up, rows), None);
+        assert_eq!(board.move_in(&corner, &Direction::Left, cols), None);
+        assert_eq!(
+            board.move_in(&corner, &Direction::Up, cols),
+            Some(Position(2, 0))
+        );
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(Board::<3, 3>::center(), Position(1, 1));
+        assert_eq!(Board::<4, 4>::center(), Position(2, 2));
+    }
+
+    #[test]
+    fn index_round_trip() {
+        for index in 0..12 {
+            let position = Board::<3, 4>::from_index(index);
+            assert_eq!(Board::<3, 4>::to_index(&position), index);
+        }
+        assert_eq!(Board::<3, 4>::from_index(5), Position(1, 1));
+        assert_eq!(Board::<3, 4>::to_index(&Position(2, 3)), 11);
+    }
+
     #[test]
     fn at() {
         let board = Board::new(INPUT_BOARD);
@@ -238,4 +390,10 @@ mod tests {
         let board: Board<3, 3> = DTO_BOARD.into();
         assert_eq!(board, Board::new(INPUT_BOARD));
     }
+
+    #[test]
+    fn to_dto() {
+        let board = Board::new(INPUT_BOARD);
+        assert_eq!(board.to_dto(), DTO_BOARD);
+    }
 }
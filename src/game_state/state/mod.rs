@@ -1,5 +1,6 @@
 pub use value_objects::*;
 
 pub mod board;
+#[allow(clippy::module_inception)]
 pub mod state;
 mod value_objects;
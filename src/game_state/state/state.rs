@@ -1,13 +1,31 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use rand_chacha::ChaCha8Rng;
 
+use crate::controller::BoardView;
 use crate::data_transfer_objects as dto;
 
-use super::{board::Board, value_objects::*};
+use super::super::options::Topology;
+use super::{
+    board::{Board, NoSnakeHead},
+    value_objects::*,
+};
 
 // TODO: add update object
-// TODO: add is_valid
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidState {
+    /// `empty`, `foods`, and `snake` do not partition the board without obstacles
+    Board,
+    /// An `empty` position does not hold `Cell::Empty` with its index
+    Empty,
+    /// A `foods` position does not hold `Cell::Foods` with its index
+    Foods,
+    /// A `snake` position does not hold `Cell::Snake`
+    Snake,
+    /// The snake paths do not lead from the head to the tail
+    Path,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct State<const N_ROWS: usize, const N_COLS: usize> {
@@ -16,23 +34,36 @@ pub struct State<const N_ROWS: usize, const N_COLS: usize> {
     pub foods: Vec<Position>,
     pub snake: VecDeque<Position>,
     pub rng: ChaCha8Rng,
+    pub topology: Topology,
 }
 
 impl<const N_ROWS: usize, const N_COLS: usize> State<N_ROWS, N_COLS> {
     pub fn new(board: Board<N_ROWS, N_COLS>, rng: ChaCha8Rng) -> State<N_ROWS, N_COLS> {
-        let empty = board.get_empty();
-        let foods = board.get_foods();
-        let snake = board.get_snake();
-        State {
+        State::try_new(board, rng).expect("snake head")
+    }
+
+    /// Like `new` but fails instead of panicking when the board has no snake head
+    pub fn try_new(
+        board: Board<N_ROWS, N_COLS>,
+        rng: ChaCha8Rng,
+    ) -> Result<State<N_ROWS, N_COLS>, NoSnakeHead> {
+        let snake = board.try_get_snake()?;
+        let state = State {
+            empty: board.get_empty(),
+            foods: board.get_foods(),
             board,
-            empty,
-            foods,
             snake,
             rng,
-        }
+            topology: Topology::default(),
+        };
+        Ok(state)
     }
 
     pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidState> {
         // A valid `State`
         // * All `Position`s in `empty`, `foods`, and `snake` are unique and have a count of
         //   `N_ROWS * N_COLS`.
@@ -41,11 +72,35 @@ impl<const N_ROWS: usize, const N_COLS: usize> State<N_ROWS, N_COLS> {
         // * `self.at(snake[i]) == Cell::Snake { .. }` for each  `i in 0..snake.len()`
         // * The snake itself is valid by having exactly one head and tail that lead to each
         // other.
-        todo!()
+        if !self.is_board_valid() {
+            Err(InvalidState::Board)
+        } else if !self.is_empty_valid() {
+            Err(InvalidState::Empty)
+        } else if !self.is_foods_valid() {
+            Err(InvalidState::Foods)
+        } else if !self.is_snake_valid() {
+            Err(InvalidState::Snake)
+        } else if !self.is_path_valid() {
+            Err(InvalidState::Path)
+        } else {
+            Ok(())
+        }
     }
 
     fn is_board_valid(&self) -> bool {
-        todo!()
+        let positions = self
+            .empty
+            .iter()
+            .chain(self.foods.iter())
+            .chain(self.snake.iter());
+        let count = self.empty.len() + self.foods.len() + self.snake.len();
+        let n_obstacles = self
+            .board
+            .iter()
+            .filter(|(_, cell)| *cell == Cell::Obstacle)
+            .count();
+        count + n_obstacles == N_ROWS * N_COLS
+            && HashSet::<&Position>::from_iter(positions).len() == count
     }
 
     fn is_empty_valid(&self) -> bool {
@@ -74,6 +129,32 @@ impl<const N_ROWS: usize, const N_COLS: usize> State<N_ROWS, N_COLS> {
             .all(|position| matches!(self.board.at(position), Cell::Snake { .. }))
     }
 
+    fn is_path_valid(&self) -> bool {
+        let is_head_valid = matches!(
+            self.snake.front().map(|head| self.board.at(head)),
+            Some(Cell::Snake(Path { exit: None, .. }))
+        );
+        let is_tail_valid = matches!(
+            self.snake.back().map(|tail| self.board.at(tail)),
+            Some(Cell::Snake(Path { entry: None, .. }))
+        );
+        let is_body_valid = self.snake.iter().zip(self.snake.iter().skip(1)).all(
+            |(position, next_position)| match self.board.at(position) {
+                Cell::Snake(Path {
+                    entry: Some(entry), ..
+                }) => {
+                    self.board.move_in(position, &entry, self.topology) == Some(*next_position)
+                        && matches!(
+                            self.board.at(next_position),
+                            Cell::Snake(Path { exit: Some(exit), .. }) if exit == entry.opposite()
+                        )
+                }
+                _ => false,
+            },
+        );
+        is_head_valid && is_tail_valid && is_body_valid
+    }
+
     pub fn check_is_won_status(&self) -> dto::Status {
         if self.empty.is_empty() && self.foods.is_empty() {
             dto::Status::Over { is_won: true }
@@ -82,9 +163,10 @@ impl<const N_ROWS: usize, const N_COLS: usize> State<N_ROWS, N_COLS> {
         }
     }
 
-    pub fn get_next_head(&self, direction: &Direction) -> Position {
+    /// The cell the head moves into, or `None` when it would leave the board
+    pub fn get_next_head(&self, direction: &Direction) -> Option<Position> {
         let head = self.snake.front().expect("snake head");
-        self.board.move_in(head, direction)
+        self.board.move_in(head, direction, self.topology)
     }
 
     pub fn remove_last_tail(&mut self) -> Position {
@@ -103,6 +185,22 @@ impl<const N_ROWS: usize, const N_COLS: usize> State<N_ROWS, N_COLS> {
     }
 }
 
+impl<const N_ROWS: usize, const N_COLS: usize> BoardView for State<N_ROWS, N_COLS> {
+    fn at(&self, position: &dto::Position) -> dto::Cell {
+        self.board.at(&(*position).into()).into()
+    }
+
+    fn head(&self) -> dto::Position {
+        (*self.snake.front().expect("snake head")).into()
+    }
+
+    fn move_in(&self, position: &dto::Position, direction: &Direction) -> Option<dto::Position> {
+        self.board
+            .move_in(&(*position).into(), direction, self.topology)
+            .map(Position::into)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::seeder::{MockSeeder, Seeder};
@@ -159,11 +257,35 @@ mod tests {
         State::new(board, rng)
     }
 
-    // #[test]
-    // fn is_valid_true() {
-    //     let state = get_mock_state();
-    //     assert!(state.is_valid());
-    // }
+    #[test]
+    fn is_valid_true() {
+        let state = get_mock_state();
+        assert!(state.is_valid());
+    }
+
+    #[test]
+    fn validate_board() {
+        let mut state = get_two_cell();
+        state.snake.push_back(Position(0, 1));
+        assert_eq!(state.validate(), Err(InvalidState::Board));
+    }
+
+    #[test]
+    fn validate_obstacle() {
+        let board = Board::new([[Cell::head(None), Cell::Obstacle]]);
+        let state = State::new(board, MockSeeder(0).get_rng());
+        assert_eq!(state.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_path() {
+        let mut state = get_mock_state();
+        *state.board.at_mut(&Position(0, 1)) = Cell::Snake(Path {
+            entry: Some(Direction::Right),
+            exit: Some(Direction::Down),
+        });
+        assert_eq!(state.validate(), Err(InvalidState::Path));
+    }
 
     #[test]
     fn is_empty_valid_false() {
@@ -183,6 +305,7 @@ mod tests {
             foods,
             snake,
             rng: MockSeeder(0).get_rng(),
+            topology: Topology::default(),
         };
         assert!(!state.is_empty_valid());
     }
@@ -205,10 +328,29 @@ mod tests {
             foods,
             snake,
             rng: MockSeeder(0).get_rng(),
+            topology: Topology::default(),
         };
         assert!(!state.is_foods_valid());
     }
 
+    #[test]
+    fn new_from_dto_interleaved() {
+        let head = dto::Cell::Snake(Path {
+            entry: None,
+            exit: None,
+        });
+        let board = Board::from([
+            [dto::Cell::Foods, dto::Cell::Empty, dto::Cell::Foods],
+            [dto::Cell::Empty, head, dto::Cell::Empty],
+            [dto::Cell::Foods, dto::Cell::Foods, dto::Cell::Empty],
+        ]);
+        let state = State::new(board, MockSeeder(0).get_rng());
+        assert!(state.is_empty_valid());
+        assert!(state.is_foods_valid());
+        assert_eq!(state.foods.len(), 4);
+        assert_eq!(state.validate(), Ok(()));
+    }
+
     #[test]
     fn is_snake_valid_false() {
         let board = Board::new([[
@@ -227,6 +369,7 @@ mod tests {
             foods,
             snake,
             rng: MockSeeder(0).get_rng(),
+            topology: Topology::default(),
         };
         assert!(!state.is_snake_valid());
     }
@@ -251,7 +394,7 @@ mod tests {
         let state = get_mock_state();
         let direction = Direction::Right;
         let head = state.get_next_head(&direction);
-        assert_eq!(head, Position(1, 2));
+        assert_eq!(head, Some(Position(1, 2)));
     }
 
     #[test]
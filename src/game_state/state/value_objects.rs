@@ -5,6 +5,13 @@ use rand::Rng;
 pub use dto::{Direction, Path}; // Re-implementation not deemed worthwhile
 
 impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+    ];
+
     pub fn get_plane(&self) -> Plane {
         match self {
             Direction::Right => Plane::Horizontal,
@@ -23,6 +30,38 @@ impl Direction {
         }
     }
 
+    /// Stable index in clockwise order starting from `Up`
+    pub fn clockwise_index(&self) -> u8 {
+        match self {
+            Direction::Up => 0,
+            Direction::Right => 1,
+            Direction::Down => 2,
+            Direction::Left => 3,
+        }
+    }
+
+    /// The four directions in clockwise order starting with `self`
+    pub fn clockwise_from(&self) -> [Direction; 4] {
+        const CLOCKWISE: [Direction; 4] = [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ];
+        let start = self.clockwise_index() as usize;
+        std::array::from_fn(|i| CLOCKWISE[(start + i) % CLOCKWISE.len()])
+    }
+
+    pub fn from_velocity(velocity: &Velocity) -> Option<Direction> {
+        match velocity {
+            Velocity(0, 1) => Some(Direction::Right),
+            Velocity(-1, 0) => Some(Direction::Up),
+            Velocity(0, -1) => Some(Direction::Left),
+            Velocity(1, 0) => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
     pub fn opposite(&self) -> Direction {
         match self {
             Direction::Right => Direction::Left,
@@ -33,6 +72,7 @@ impl Direction {
     }
 }
 
+/// Uniform over the four directions, so the range must keep exactly one value per direction
 impl Distribution<Direction> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
         match rng.gen_range(0..4) {
@@ -65,6 +105,21 @@ mod direction_tests {
         assert_eq!(direction, Direction::Left);
     }
 
+    #[test]
+    fn sample_uniform() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let n_samples = 40_000;
+        let mut counts = [0_usize; 4];
+        for _ in 0..n_samples {
+            let direction: Direction = Distribution::sample(&Standard, &mut rng);
+            counts[direction.clockwise_index() as usize] += 1;
+        }
+        let expected = n_samples / counts.len();
+        for count in counts {
+            assert!(count.abs_diff(expected) < expected / 20, "{counts:?}");
+        }
+    }
+
     #[test]
     fn as_velocity() {
         assert_eq!(Direction::Right.as_velocity(), Velocity(0, 1));
@@ -73,6 +128,53 @@ mod direction_tests {
         assert_eq!(Direction::Down.as_velocity(), Velocity(1, 0));
     }
 
+    #[test]
+    fn clockwise_index() {
+        let mut directions = Direction::ALL;
+        directions.sort_by_key(Direction::clockwise_index);
+        assert_eq!(
+            directions,
+            [
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left
+            ]
+        );
+        for (index, direction) in directions.iter().enumerate() {
+            let Velocity(i, j) = direction.as_velocity();
+            let next = directions[(index + 1) % directions.len()];
+            assert_eq!(next.as_velocity(), Velocity(j, -i));
+            assert_eq!(
+                Direction::from_velocity(&direction.as_velocity()),
+                Some(*direction)
+            );
+        }
+        assert_eq!(Direction::from_velocity(&Velocity(1, 1)), None);
+    }
+
+    #[test]
+    fn clockwise_from() {
+        assert_eq!(
+            Direction::Up.clockwise_from(),
+            [
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left
+            ]
+        );
+        assert_eq!(
+            Direction::Left.clockwise_from(),
+            [
+                Direction::Left,
+                Direction::Up,
+                Direction::Right,
+                Direction::Down
+            ]
+        );
+    }
+
     #[test]
     fn opposite() {
         assert_eq!(Direction::Right.opposite(), Direction::Left);
@@ -82,12 +184,51 @@ mod direction_tests {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Plane {
     Horizontal,
     Vertical,
 }
 
+impl Plane {
+    pub fn directions(&self) -> [Direction; 2] {
+        match self {
+            Plane::Horizontal => [Direction::Right, Direction::Left],
+            Plane::Vertical => [Direction::Up, Direction::Down],
+        }
+    }
+
+    pub fn other(&self) -> Plane {
+        match self {
+            Plane::Horizontal => Plane::Vertical,
+            Plane::Vertical => Plane::Horizontal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod plane_tests {
+    use super::*;
+
+    #[test]
+    fn directions() {
+        assert_eq!(
+            Plane::Horizontal.directions(),
+            [Direction::Right, Direction::Left]
+        );
+        assert_eq!(
+            Plane::Vertical.directions(),
+            [Direction::Up, Direction::Down]
+        );
+    }
+
+    #[test]
+    fn other() {
+        assert_eq!(Plane::Horizontal.other(), Plane::Vertical);
+        assert_eq!(Plane::Vertical.other(), Plane::Horizontal);
+    }
+}
+
 #[derive(PartialEq, Hash, Eq, Debug, Copy, Clone)]
 pub struct Position(pub usize, pub usize);
 
@@ -97,6 +238,12 @@ impl From<Position> for dto::Position {
     }
 }
 
+impl From<dto::Position> for Position {
+    fn from((i, j): dto::Position) -> Self {
+        Position(i, j)
+    }
+}
+
 #[cfg(test)]
 mod position_tests {
     use super::*;
@@ -108,6 +255,11 @@ mod position_tests {
         let actual = DtoPosition::from(position);
         assert_eq!(actual, (0, 1));
     }
+
+    #[test]
+    fn from_dto() {
+        assert_eq!(Position::from((0, 1)), Position(0, 1));
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -124,6 +276,24 @@ pub enum Cell {
     Empty(usize),
     Foods(usize),
     Snake(Path),
+    Obstacle,
+}
+
+impl Cell {
+    pub const fn head(entry: Option<Direction>) -> Cell {
+        Cell::Snake(Path { entry, exit: None })
+    }
+
+    pub const fn tail(exit: Option<Direction>) -> Cell {
+        Cell::Snake(Path { entry: None, exit })
+    }
+
+    pub const fn body(entry: Direction, exit: Direction) -> Cell {
+        Cell::Snake(Path {
+            entry: Some(entry),
+            exit: Some(exit),
+        })
+    }
 }
 
 impl From<Cell> for dto::Cell {
@@ -132,6 +302,7 @@ impl From<Cell> for dto::Cell {
             Cell::Empty(_) => dto::Cell::Empty,
             Cell::Foods(_) => dto::Cell::Foods,
             Cell::Snake(path) => dto::Cell::Snake(path),
+            Cell::Obstacle => dto::Cell::Obstacle,
         }
     }
 }
@@ -140,6 +311,33 @@ impl From<Cell> for dto::Cell {
 mod cell_tests {
     use super::*;
 
+    #[test]
+    fn head() {
+        let expected = Cell::Snake(Path {
+            entry: Some(Direction::Down),
+            exit: None,
+        });
+        assert_eq!(Cell::head(Some(Direction::Down)), expected);
+    }
+
+    #[test]
+    fn tail() {
+        let expected = Cell::Snake(Path {
+            entry: None,
+            exit: Some(Direction::Up),
+        });
+        assert_eq!(Cell::tail(Some(Direction::Up)), expected);
+    }
+
+    #[test]
+    fn body() {
+        let expected = Cell::Snake(Path {
+            entry: Some(Direction::Left),
+            exit: Some(Direction::Right),
+        });
+        assert_eq!(Cell::body(Direction::Left, Direction::Right), expected);
+    }
+
     #[test]
     fn empty_into() {
         let actual = dto::Cell::from(Cell::Empty(0));
@@ -152,6 +350,12 @@ mod cell_tests {
         assert_eq!(actual, dto::Cell::Foods);
     }
 
+    #[test]
+    fn obstacle_into() {
+        let actual = dto::Cell::from(Cell::Obstacle);
+        assert_eq!(actual, dto::Cell::Obstacle);
+    }
+
     #[test]
     fn snake_from_into() {
         let actual: dto::Cell = Cell::Snake(Path {
@@ -0,0 +1,198 @@
+use crate::controller::Controller;
+use crate::seeder::MockSeeder;
+use crate::view::View;
+
+use super::{
+    options::{Options, Settings},
+    state::Direction,
+    GameState,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidTrace;
+
+/// Everything needed to reproduce a game built from `Options`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trace {
+    pub seed: u64,
+    pub n_rows: usize,
+    pub n_cols: usize,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub settings: Settings,
+    pub directions: Vec<Direction>,
+}
+
+impl<'a, const N_ROWS: usize, const N_COLS: usize> GameState<'a, N_ROWS, N_COLS> {
+    /// The trace of a game built from `Options`, recording the settings it was built with since
+    /// it doesn't capture `set_n_foods` or `feed`
+    pub fn export_trace(&self) -> Option<Trace> {
+        Some(Trace {
+            seed: self.seed?,
+            n_rows: N_ROWS,
+            n_cols: N_COLS,
+            settings: self.settings.clone(),
+            directions: self.directions.clone(),
+        })
+    }
+
+    /// The trace as URL-safe base64 JSON for sharing a game as a link
+    #[cfg(feature = "compact")]
+    pub fn serialize_compact(&self) -> Option<String> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        let json = serde_json::to_vec(&self.export_trace()?).expect("trace to json");
+        Some(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    /// Rebuilds the game shared by `serialize_compact`
+    #[cfg(feature = "compact")]
+    pub fn deserialize_compact(
+        compact: &str,
+        controller: &'a mut dyn Controller,
+        view: &'a mut dyn View,
+    ) -> Result<GameState<'a, N_ROWS, N_COLS>, InvalidTrace> {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        let json = URL_SAFE_NO_PAD.decode(compact).map_err(|_| InvalidTrace)?;
+        let trace = serde_json::from_slice(&json).map_err(|_| InvalidTrace)?;
+        GameState::import(&trace, controller, view)
+    }
+
+    /// Rebuilds the game and applies every traced direction
+    pub fn import(
+        trace: &Trace,
+        controller: &'a mut dyn Controller,
+        view: &'a mut dyn View,
+    ) -> Result<GameState<'a, N_ROWS, N_COLS>, InvalidTrace> {
+        if (trace.n_rows, trace.n_cols) != (N_ROWS, N_COLS) {
+            return Err(InvalidTrace);
+        }
+        let options = Options::<N_ROWS, N_COLS> {
+            seeder: Box::new(MockSeeder(trace.seed)),
+            settings: trace.settings.clone(),
+        };
+        let mut game_state = options.build(controller, view).map_err(|_| InvalidTrace)?;
+        for direction in &trace.directions {
+            game_state.iterate_turn_with(*direction);
+        }
+        Ok(game_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::controller::mock_controller::{MockController, ScriptedController};
+    use crate::data_transfer_objects as dto;
+    use crate::view::NullView;
+
+    use super::*;
+    use crate::game_state::FoodSeeding;
+
+    const DIRECTIONS: [Direction; 6] = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+        Direction::Up,
+    ];
+
+    #[test]
+    fn export_trace_import() {
+        let mut controller_a = ScriptedController(VecDeque::from(DIRECTIONS));
+        let mut view_a = NullView;
+        let mut options = Options::<4, 5>::with_seed(3, 2)
+            .with_obstacles(&[(0, 0)])
+            .with_seeded_heading();
+        options.settings.food_seeding = FoodSeeding::BeforeEmpty;
+        let mut game_state_a = options.build(&mut controller_a, &mut view_a).unwrap();
+        for _ in DIRECTIONS {
+            game_state_a.iterate_turn();
+        }
+        let trace = game_state_a.export_trace().unwrap();
+        assert_eq!(trace.directions, DIRECTIONS);
+        assert_eq!(trace.settings.obstacles, [(0, 0)]);
+        assert_eq!(game_state_a.status(), dto::Status::Ongoing);
+        assert!(trace.settings.seeded_heading);
+        let mut controller_b = MockController(Direction::Right);
+        let mut view_b = NullView;
+        let game_state_b =
+            GameState::<4, 5>::import(&trace, &mut controller_b, &mut view_b).unwrap();
+        assert_eq!(game_state_a.state, game_state_b.state);
+        assert_eq!(game_state_a.score(), game_state_b.score());
+    }
+
+    #[test]
+    fn export_trace_after_set_n_foods() {
+        let mut controller = MockController(Direction::Right);
+        let mut view = NullView;
+        let mut game_state = Options::<3, 3>::with_seed(1, 0)
+            .build(&mut controller, &mut view)
+            .unwrap();
+        game_state.set_n_foods(9);
+        let trace = game_state.export_trace().unwrap();
+        assert_eq!(trace.settings.n_foods, 1);
+        let mut controller = MockController(Direction::Right);
+        let mut view = NullView;
+        let game_state = GameState::<3, 3>::import(&trace, &mut controller, &mut view).unwrap();
+        assert_eq!(game_state.food_target(), 1);
+    }
+
+    #[test]
+    fn import_shape_mismatch() {
+        let trace = Trace {
+            seed: 0,
+            n_rows: 4,
+            n_cols: 5,
+            settings: Settings::default(),
+            directions: Vec::new(),
+        };
+        let mut controller = MockController(Direction::Right);
+        let mut view = NullView;
+        let result = GameState::<5, 4>::import(&trace, &mut controller, &mut view);
+        assert_eq!(result.unwrap_err(), InvalidTrace);
+    }
+
+    #[cfg(feature = "compact")]
+    #[test]
+    fn serialize_compact() {
+        let mut controller_a = ScriptedController(VecDeque::from(DIRECTIONS.to_vec()));
+        let mut view_a = NullView;
+        let mut game_state_a = Options::<4, 5>::with_seed(2, 7)
+            .build(&mut controller_a, &mut view_a)
+            .unwrap();
+        for _ in DIRECTIONS {
+            game_state_a.iterate_turn();
+        }
+        let compact = game_state_a.serialize_compact().unwrap();
+        assert!(compact
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        let mut controller_b = MockController(Direction::Right);
+        let mut view_b = NullView;
+        let game_state_b =
+            GameState::<4, 5>::deserialize_compact(&compact, &mut controller_b, &mut view_b)
+                .unwrap();
+        assert_eq!(game_state_a.state, game_state_b.state);
+        assert_eq!(game_state_a.score(), game_state_b.score());
+        let mut controller_c = MockController(Direction::Right);
+        let mut view_c = NullView;
+        let result = GameState::<4, 5>::deserialize_compact("!", &mut controller_c, &mut view_c);
+        assert_eq!(result.unwrap_err(), InvalidTrace);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn trace_serde() {
+        let trace = Trace {
+            seed: 0,
+            n_rows: 4,
+            n_cols: 5,
+            settings: Settings::default(),
+            directions: DIRECTIONS.to_vec(),
+        };
+        let json = serde_json::to_string(&trace).unwrap();
+        assert_eq!(serde_json::from_str::<Trace>(&json).unwrap(), trace);
+    }
+}
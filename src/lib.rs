@@ -1,5 +1,59 @@
+use std::thread;
+use std::time::Duration;
+
+use controller::Controller;
+use data_transfer_objects as dto;
+use game_state::{InvalidOptions, Options, SimulationResult};
+use view::View;
+
 pub mod controller;
 pub mod data_transfer_objects;
 pub mod game_state;
 pub mod seeder;
 pub mod view;
+
+/// Builds a game from `options` and plays a turn every `tick` until it's over, the view
+/// drawing each turn as its cells change
+pub fn play<const N_ROWS: usize, const N_COLS: usize>(
+    options: &Options<N_ROWS, N_COLS>,
+    controller: &mut dyn Controller,
+    view: &mut dyn View,
+    tick: Duration,
+) -> Result<SimulationResult, InvalidOptions> {
+    let mut game_state = options.build(controller, view)?;
+    let mut n_turns = 0;
+    while game_state.status() == dto::Status::Ongoing {
+        thread::sleep(tick);
+        game_state.iterate_turn();
+        n_turns += 1;
+    }
+    Ok(SimulationResult {
+        status: game_state.status(),
+        score: game_state.score(),
+        n_turns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::mock_controller::MockController;
+    use crate::data_transfer_objects::Direction;
+    use crate::view::NullView;
+
+    #[test]
+    fn play() {
+        let mut controller = MockController(Direction::Right);
+        let mut view = NullView;
+        let options = Options::<1, 2>::with_seed(1, 0);
+        let result = super::play(&options, &mut controller, &mut view, Duration::ZERO);
+        assert_eq!(
+            result,
+            Ok(SimulationResult {
+                status: dto::Status::Over { is_won: true },
+                score: 1,
+                n_turns: 1,
+            })
+        );
+    }
+}
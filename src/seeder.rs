@@ -1,3 +1,4 @@
+use std::env;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use rand::SeedableRng;
@@ -38,10 +39,48 @@ impl Seeder for MockSeeder {
     }
 }
 
+/// Reads the seed from `SNAKE_SEED` to pin a run, falling back to `SecondsSeeder` when it's
+/// unset or not a `u64`
+#[derive(Default)]
+pub struct EnvSeeder;
+
+impl EnvSeeder {
+    pub const VAR: &'static str = "SNAKE_SEED";
+}
+
+impl Seeder for EnvSeeder {
+    fn get_seed(&self) -> u64 {
+        parse_seed(env::var(EnvSeeder::VAR).ok().as_deref())
+            .unwrap_or_else(|| SecondsSeeder::SECONDS_SEEDER.get_seed())
+    }
+}
+
+fn parse_seed(seed: Option<&str>) -> Option<u64> {
+    seed?.trim().parse().ok()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
     use super::*;
 
+    /// Held while a test touches `EnvSeeder::VAR` so parallel tests don't race on it
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn env_seeder_get_seed_with(seed: Option<&str>) -> u64 {
+        let _lock = ENV_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match seed {
+            Some(seed) => env::set_var(EnvSeeder::VAR, seed),
+            None => env::remove_var(EnvSeeder::VAR),
+        }
+        let got = EnvSeeder.get_seed();
+        env::remove_var(EnvSeeder::VAR);
+        got
+    }
+
     #[test]
     fn seconds_seeder_get_secs() {
         SecondsSeeder::SECONDS_SEEDER.get_seed();
@@ -51,4 +90,32 @@ mod tests {
     fn mock_seeder_get_secs() {
         assert_eq!(MockSeeder(0).get_seed(), 0);
     }
+
+    #[test]
+    fn parse_seed() {
+        assert_eq!(super::parse_seed(Some("42")), Some(42));
+        assert_eq!(super::parse_seed(Some(" 42\n")), Some(42));
+        assert_eq!(super::parse_seed(Some("forty-two")), None);
+        assert_eq!(super::parse_seed(Some("-1")), None);
+        assert_eq!(super::parse_seed(None), None);
+    }
+
+    #[test]
+    fn env_seeder_get_seed_set() {
+        assert_eq!(env_seeder_get_seed_with(Some("42")), 42);
+    }
+
+    #[test]
+    fn env_seeder_get_seed_unset() {
+        let before = SecondsSeeder::SECONDS_SEEDER.get_seed();
+        let got = env_seeder_get_seed_with(None);
+        assert!((before..=SecondsSeeder::SECONDS_SEEDER.get_seed()).contains(&got));
+    }
+
+    #[test]
+    fn env_seeder_get_seed_invalid() {
+        let before = SecondsSeeder::SECONDS_SEEDER.get_seed();
+        let got = env_seeder_get_seed_with(Some("forty-two"));
+        assert!((before..=SecondsSeeder::SECONDS_SEEDER.get_seed()).contains(&got));
+    }
 }
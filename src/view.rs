@@ -1,18 +1,214 @@
+use std::any::Any;
 use std::fmt::Debug;
+use std::io::{self, Write};
 
 use crate::data_transfer_objects as dto;
 
-pub trait View: Debug {
+pub trait View: Debug + Any {
     fn swap_cell(&mut self, position: &dto::Position, new: dto::Cell);
+
+    /// The head wrapped from `from` to `to`, adjacent on the torus but across the board
+    fn seam(&mut self, _from: &dto::Position, _to: &dto::Position) {}
+
+    /// The game just ended, called once however many turns are played after
+    fn on_game_over(&mut self, _is_won: bool) {}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CellTheme {
+    pub empty: char,
+    pub food: char,
+    pub snake: char,
+    pub obstacle: char,
+}
+
+impl Default for CellTheme {
+    fn default() -> Self {
+        CellTheme {
+            empty: '░',
+            food: '▒',
+            snake: '█',
+            obstacle: '▓',
+        }
+    }
+}
+
+impl CellTheme {
+    pub fn glyph(&self, cell: dto::Cell) -> char {
+        match cell {
+            dto::Cell::Empty => self.empty,
+            dto::Cell::Foods => self.food,
+            dto::Cell::Snake(_) => self.snake,
+            dto::Cell::Obstacle => self.obstacle,
+        }
+    }
+}
+
+/// Ignores every update for headless games
+#[derive(Default, Debug)]
+pub struct NullView;
+
+impl View for NullView {
+    fn swap_cell(&mut self, _position: &dto::Position, _new: dto::Cell) {}
+}
+
+/// Accumulates swaps so the caller can render them together once per turn, reaching it
+/// through `GameState::view_mut`
+#[derive(Default, Debug)]
+pub struct BufferedView {
+    swaps: Vec<(dto::Position, dto::Cell)>,
+    seams: Vec<(dto::Position, dto::Position)>,
+}
+
+impl BufferedView {
+    /// The swaps since the last flush, oldest first
+    pub fn flush(&mut self) -> Vec<(dto::Position, dto::Cell)> {
+        std::mem::take(&mut self.swaps)
+    }
+
+    /// The seams crossed since the last flush, oldest first
+    pub fn flush_seams(&mut self) -> Vec<(dto::Position, dto::Position)> {
+        std::mem::take(&mut self.seams)
+    }
+}
+
+impl View for BufferedView {
+    fn swap_cell(&mut self, position: &dto::Position, new: dto::Cell) {
+        self.swaps.push((*position, new));
+    }
+
+    fn seam(&mut self, from: &dto::Position, to: &dto::Position) {
+        self.seams.push((*from, *to));
+    }
+}
+
+/// Draws to a terminal by keeping the previous frame and only moving the cursor to rewrite the
+/// cells that changed, assuming the screen starts out as empty cells
+#[derive(Debug)]
+pub struct TerminalView<W: Write + Debug> {
+    writer: W,
+    theme: CellTheme,
+    frame: Vec<Vec<char>>,
+    pending: Vec<(dto::Position, dto::Cell)>,
+}
+
+impl<W: Write + Debug> TerminalView<W> {
+    pub fn new(writer: W, n_rows: usize, n_cols: usize, theme: CellTheme) -> Self {
+        TerminalView {
+            writer,
+            theme,
+            frame: vec![vec![theme.empty; n_cols]; n_rows],
+            pending: Vec::new(),
+        }
+    }
+
+    /// Writes the cells whose glyph changed since the last present
+    pub fn present(&mut self) -> io::Result<()> {
+        let mut frame = self.frame.clone();
+        for ((i, j), cell) in std::mem::take(&mut self.pending) {
+            frame[i][j] = self.theme.glyph(cell);
+        }
+        for (i, (row, last_row)) in frame.iter().zip(&self.frame).enumerate() {
+            for (j, (glyph, last_glyph)) in row.iter().zip(last_row).enumerate() {
+                if glyph != last_glyph {
+                    write!(self.writer, "\x1b[{};{}H{}", i + 1, j + 1, glyph)?;
+                }
+            }
+        }
+        self.frame = frame;
+        self.writer.flush()
+    }
+
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+}
+
+impl<W: Write + Debug + 'static> View for TerminalView<W> {
+    fn swap_cell(&mut self, position: &dto::Position, new: dto::Cell) {
+        self.pending.push((*position, new));
+    }
+}
+
+/// Rebuilds the board from swaps alone, starting out as empty cells unless given a frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardBuilderView {
+    frame: dto::Frame,
+}
+
+impl BoardBuilderView {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        BoardBuilderView::from_frame(vec![vec![dto::Cell::Empty; n_cols]; n_rows])
+    }
+
+    pub fn from_frame(frame: dto::Frame) -> Self {
+        BoardBuilderView { frame }
+    }
+
+    pub fn frame(&self) -> &dto::Frame {
+        &self.frame
+    }
+}
+
+impl View for BoardBuilderView {
+    fn swap_cell(&mut self, (i, j): &dto::Position, new: dto::Cell) {
+        self.frame[*i][*j] = new;
+    }
+}
+
+/// Checks the board rebuilt from swaps against the engine's to catch bookkeeping drift,
+/// recording which checks mismatched
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyingView {
+    builder: BoardBuilderView,
+    n_checks: usize,
+    mismatches: Vec<usize>,
+}
+
+impl VerifyingView {
+    /// Starts from `frame`, which building a game overwrites as it sends every cell
+    pub fn new(frame: dto::Frame) -> Self {
+        VerifyingView {
+            builder: BoardBuilderView::from_frame(frame),
+            n_checks: 0,
+            mismatches: Vec::new(),
+        }
+    }
+
+    /// Whether the rebuilt board equals `frame`, recording the check when it doesn't
+    pub fn verify(&mut self, frame: &dto::Frame) -> bool {
+        let is_match = self.builder.frame() == frame;
+        if !is_match {
+            self.mismatches.push(self.n_checks);
+        }
+        self.n_checks += 1;
+        is_match
+    }
+
+    /// The zero-based indices of the checks that mismatched
+    pub fn mismatches(&self) -> &[usize] {
+        &self.mismatches
+    }
+}
+
+impl View for VerifyingView {
+    fn swap_cell(&mut self, position: &dto::Position, new: dto::Cell) {
+        self.builder.swap_cell(position, new);
+    }
 }
 
+/// Records swaps and game over events in order
 #[derive(Default, Debug)]
-pub struct MockView(pub Vec<(dto::Position, dto::Cell)>);
+pub struct MockView(pub Vec<(dto::Position, dto::Cell)>, pub Vec<bool>);
 
 impl View for MockView {
     fn swap_cell(&mut self, position: &dto::Position, new: dto::Cell) {
         self.0.push((*position, new));
     }
+
+    fn on_game_over(&mut self, is_won: bool) {
+        self.1.push(is_won);
+    }
 }
 
 #[cfg(test)]
@@ -27,4 +223,181 @@ mod test {
         view.swap_cell(&position, new);
         assert_eq!(view.0, [(position, new)]);
     }
+
+    #[test]
+    fn on_game_over() {
+        let mut view = MockView::default();
+        view.on_game_over(true);
+        assert_eq!(view.1, [true]);
+    }
+
+    #[test]
+    fn buffered_view_flush() {
+        let mut view = BufferedView::default();
+        view.swap_cell(&(0, 1), dto::Cell::Empty);
+        view.swap_cell(&(1, 0), dto::Cell::Foods);
+        assert_eq!(
+            view.flush(),
+            [((0, 1), dto::Cell::Empty), ((1, 0), dto::Cell::Foods)]
+        );
+        assert_eq!(view.flush(), []);
+    }
+
+    #[test]
+    fn buffered_view_flush_per_turn() {
+        use crate::controller::mock_controller::MockController;
+        use crate::game_state::Options;
+
+        let snake = dto::Cell::Snake(dto::Path {
+            entry: None,
+            exit: None,
+        });
+        let mut controller = MockController(dto::Direction::Right);
+        let mut view = BufferedView::default();
+        let mut game_state = Options::<1, 3>::with_seed(0, 0)
+            .build(&mut controller, &mut view)
+            .unwrap();
+        assert!(game_state.view_mut::<MockView>().is_none());
+        game_state.view_mut::<BufferedView>().unwrap().flush();
+        game_state.iterate_turn();
+        assert_eq!(
+            game_state.view_mut::<BufferedView>().unwrap().flush(),
+            [((0, 1), dto::Cell::Empty), ((0, 2), snake)]
+        );
+        game_state.iterate_turn();
+        assert_eq!(
+            game_state.view_mut::<BufferedView>().unwrap().flush(),
+            [((0, 2), dto::Cell::Empty), ((0, 0), snake)]
+        );
+    }
+
+    #[test]
+    fn buffered_view_flush_seams() {
+        let mut view = BufferedView::default();
+        view.seam(&(0, 2), &(0, 0));
+        assert_eq!(view.flush_seams(), [((0, 2), (0, 0))]);
+        assert_eq!(view.flush_seams(), []);
+    }
+
+    #[test]
+    fn verifying_view() {
+        use crate::controller::mock_controller::MockController;
+        use crate::game_state::Options;
+
+        let options = Options::<4, 4>::with_seed(2, 3);
+        let mut controller = MockController(dto::Direction::Right);
+        let mut null_view = NullView;
+        let (initial, _, _) = options
+            .build(&mut controller, &mut null_view)
+            .unwrap()
+            .snapshot_dto();
+        let mut controller = MockController(dto::Direction::Right);
+        let mut view = VerifyingView::new(initial);
+        let mut game_state = options.build(&mut controller, &mut view).unwrap();
+        for _ in 0..12 {
+            let frame = game_state.snapshot_dto().0;
+            assert!(game_state
+                .view_mut::<VerifyingView>()
+                .unwrap()
+                .verify(&frame));
+            game_state.iterate_turn();
+        }
+        let frame = game_state.snapshot_dto().0;
+        let view = game_state.view_mut::<VerifyingView>().unwrap();
+        assert!(view.verify(&frame));
+        assert!(view.mismatches().is_empty());
+    }
+
+    #[test]
+    fn verifying_view_mismatch() {
+        let mut view = VerifyingView::new(vec![vec![dto::Cell::Empty; 2]]);
+        assert!(view.verify(&vec![vec![dto::Cell::Empty; 2]]));
+        view.swap_cell(&(0, 1), dto::Cell::Foods);
+        assert!(!view.verify(&vec![vec![dto::Cell::Empty; 2]]));
+        assert_eq!(view.mismatches(), [1]);
+    }
+
+    #[test]
+    fn board_builder_view() {
+        let mut view = BoardBuilderView::new(2, 2);
+        view.swap_cell(&(1, 0), dto::Cell::Foods);
+        assert_eq!(
+            view.frame(),
+            &[
+                [dto::Cell::Empty, dto::Cell::Empty],
+                [dto::Cell::Foods, dto::Cell::Empty]
+            ]
+        );
+    }
+
+    #[test]
+    fn terminal_view_present() {
+        let theme = CellTheme {
+            empty: '.',
+            food: '*',
+            snake: '#',
+            obstacle: 'X',
+        };
+        let snake = dto::Cell::Snake(dto::Path {
+            entry: None,
+            exit: None,
+        });
+        let mut view = TerminalView::new(Vec::new(), 2, 3, theme);
+        view.swap_cell(&(0, 1), snake);
+        view.swap_cell(&(1, 2), dto::Cell::Foods);
+        view.swap_cell(&(1, 0), dto::Cell::Empty);
+        view.present().unwrap();
+        assert_eq!(view.writer(), b"\x1b[1;2H#\x1b[2;3H*");
+        view.swap_cell(&(0, 1), dto::Cell::Empty);
+        view.swap_cell(&(0, 2), snake);
+        view.swap_cell(&(1, 2), dto::Cell::Foods);
+        view.swap_cell(&(1, 1), dto::Cell::Foods);
+        view.swap_cell(&(1, 1), dto::Cell::Empty);
+        view.present().unwrap();
+        assert_eq!(view.writer(), b"\x1b[1;2H#\x1b[2;3H*\x1b[1;2H.\x1b[1;3H#");
+    }
+
+    #[test]
+    fn terminal_view_present_built_game() {
+        use crate::controller::mock_controller::MockController;
+        use crate::game_state::Options;
+
+        let theme = CellTheme {
+            empty: '.',
+            food: '*',
+            snake: '#',
+            obstacle: 'X',
+        };
+        let mut controller = MockController(dto::Direction::Right);
+        let mut view = TerminalView::new(Vec::new(), 3, 3, theme);
+        let mut game_state = Options::<3, 3>::with_seed(0, 0)
+            .with_obstacles(&[(0, 0)])
+            .build(&mut controller, &mut view)
+            .unwrap();
+        let view = game_state.view_mut::<TerminalView<Vec<u8>>>().unwrap();
+        view.present().unwrap();
+        assert_eq!(view.writer(), b"\x1b[1;1HX\x1b[2;2H#");
+        game_state.iterate_turn();
+        let view = game_state.view_mut::<TerminalView<Vec<u8>>>().unwrap();
+        view.present().unwrap();
+        assert_eq!(view.writer(), b"\x1b[1;1HX\x1b[2;2H#\x1b[2;2H.\x1b[2;3H#");
+    }
+
+    #[test]
+    fn cell_theme_glyph() {
+        let theme = CellTheme {
+            empty: '.',
+            food: '*',
+            snake: '#',
+            obstacle: 'X',
+        };
+        let path = dto::Path {
+            entry: None,
+            exit: None,
+        };
+        assert_eq!(theme.glyph(dto::Cell::Empty), '.');
+        assert_eq!(theme.glyph(dto::Cell::Foods), '*');
+        assert_eq!(theme.glyph(dto::Cell::Snake(path)), '#');
+        assert_eq!(theme.glyph(dto::Cell::Obstacle), 'X');
+    }
 }